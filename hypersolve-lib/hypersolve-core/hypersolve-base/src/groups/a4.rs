@@ -117,6 +117,15 @@ impl A4 {
         }
     }
 
+    /// Returns the order of the element, the lowest positive power `n` for which `self^n` is the identity
+    pub const fn order(self) -> usize {
+        match self {
+            A4::E => 1,
+            A4::U1 | A4::U2 | A4::U3 => 2,
+            _ => 3,
+        }
+    }
+
     pub const fn to_c3(self) -> super::C3 {
         // SAFETY: % 3 ensures discriminant <= 2
         unsafe { super::C3::from_repr_unchecked(self as u8 % 3) }
@@ -148,4 +157,16 @@ mod tests {
         assert_eq!(A4::U3 * A4::R3, A4::R6);
         assert_eq!(A4::E * A4::R8, A4::R8);
     }
+
+    #[test]
+    fn a4_order() {
+        for &elem in A4::VARIANTS {
+            let order = elem.order();
+            let mut power = A4::E;
+            for n in 1..=order {
+                power = power * elem;
+                assert_eq!(power == A4::E, n == order, "{elem:?}");
+            }
+        }
+    }
 }
@@ -1,7 +1,5 @@
 use const_for::const_for;
 
-use crate::assert_unchecked;
-
 /// Computes factorials with a loop
 pub const fn factorial(n: u64) -> u64 {
     let mut prod = 1;
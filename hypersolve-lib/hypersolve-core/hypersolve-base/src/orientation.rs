@@ -27,6 +27,10 @@ where
     pub const fn from_array(array: [T; 15]) -> Self {
         Orientation(array)
     }
+
+    pub const fn as_array(&self) -> &[T; 15] {
+        &self.0
+    }
 }
 
 impl Orientation<A4> {
@@ -0,0 +1,745 @@
+use std::fmt::Display;
+use std::ops::Range;
+
+use itertools::Itertools;
+use strum::VariantArray;
+
+use super::*;
+
+/// Start of a line comment
+const COMMENT_START: &str = "//";
+
+/// Maximum number of twists suggested for an invalid twist
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Maximum number of twists an algorithm can expand to, which keeps nested repetitions,
+/// commutators and conjugates from expanding to more twists than fit in memory
+const MAX_EXPANDED_LEN: u64 = 1_000_000;
+
+/// Errors for parsing algorithms
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ParseAlgorithmError {
+    #[error("invalid twist `{0}`: {1}")]
+    InvalidTwist(String, ParseTwistError),
+    #[error("unexpected `{0}`")]
+    UnexpectedChar(char),
+    #[error("missing closing `{0}`")]
+    MissingClosing(char),
+    #[error("missing `,` or `:` in brackets")]
+    MissingSeparator,
+    #[error("invalid repetition count `{0}`")]
+    InvalidCount(String),
+    #[error("expands to more than {} twists", MAX_EXPANDED_LEN)]
+    TooLong,
+}
+
+/// A [`ParseAlgorithmError`] with its location in the parsed string
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{error}")]
+pub struct SpannedParseError {
+    /// The error
+    pub error: ParseAlgorithmError,
+    /// Byte range of the parsed string with the error
+    pub span: Range<usize>,
+    /// Index of the twist with the error, or the number of twists before the error if it isn't in
+    /// a twist
+    pub token_index: usize,
+    /// Valid twists close to an invalid twist
+    pub suggestions: Vec<String>,
+}
+
+/// A part of an [`Algorithm`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlgorithmNode {
+    /// A single twist
+    Twist(Twist),
+    /// A parenthesized group of nodes `(A)`
+    Group(Algorithm),
+    /// A commutator `[A, B]` which expands to `A B A' B'`
+    Commutator(Algorithm, Algorithm),
+    /// A conjugate `[A: B]` which expands to `A B A'`
+    Conjugate(Algorithm, Algorithm),
+    /// A node repeated a number of times `(A)n`
+    Repeat(Box<AlgorithmNode>, u32),
+    /// The inverse of a node `(A)'`
+    Inverse(Box<AlgorithmNode>),
+    /// A line comment `// comment`
+    Comment(String),
+    /// A line break
+    LineBreak,
+}
+
+/// A twist sequence which keeps the structure it was written with
+///
+/// Algorithms are written as twists separated by whitespace which may be grouped:
+/// * `(A)` groups twists, and a group may be followed by a repetition count `(A)3` or a `'` to
+///   invert it `(A)'`
+/// * `[A, B]` is the commutator `A B A' B'`
+/// * `[A: B]` is the conjugate `A B A'`
+/// * `//` starts a comment which runs to the end of the line
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Algorithm(pub Vec<AlgorithmNode>);
+
+impl AlgorithmNode {
+    /// Returns the twists this node expands to
+    pub fn expand(&self) -> TwistSequence {
+        let mut twists = Vec::new();
+        self.expand_into(&mut twists);
+        TwistSequence(twists)
+    }
+
+    /// Returns the number of twists this node expands to without expanding it
+    fn expanded_len(&self) -> u64 {
+        match self {
+            AlgorithmNode::Twist(_) => 1,
+            AlgorithmNode::Group(alg) => alg.expanded_len(),
+            AlgorithmNode::Commutator(a, b) => a
+                .expanded_len()
+                .saturating_add(b.expanded_len())
+                .saturating_mul(2),
+            AlgorithmNode::Conjugate(a, b) => a
+                .expanded_len()
+                .saturating_mul(2)
+                .saturating_add(b.expanded_len()),
+            AlgorithmNode::Repeat(node, count) => node.expanded_len().saturating_mul(*count as u64),
+            AlgorithmNode::Inverse(node) => node.expanded_len(),
+            AlgorithmNode::Comment(_) | AlgorithmNode::LineBreak => 0,
+        }
+    }
+
+    fn expand_into(&self, twists: &mut Vec<Twist>) {
+        match self {
+            AlgorithmNode::Twist(twist) => twists.push(*twist),
+            AlgorithmNode::Group(alg) => alg.expand_into(twists),
+            AlgorithmNode::Commutator(a, b) => {
+                a.expand_into(twists);
+                b.expand_into(twists);
+                twists.extend(a.expand().inverse());
+                twists.extend(b.expand().inverse());
+            }
+            AlgorithmNode::Conjugate(a, b) => {
+                a.expand_into(twists);
+                b.expand_into(twists);
+                twists.extend(a.expand().inverse());
+            }
+            AlgorithmNode::Repeat(node, count) => {
+                let node = node.expand();
+                for _ in 0..*count {
+                    twists.extend_from_slice(&node);
+                }
+            }
+            AlgorithmNode::Inverse(node) => twists.extend(node.expand().inverse()),
+            AlgorithmNode::Comment(_) | AlgorithmNode::LineBreak => (),
+        }
+    }
+
+    /// Returns the node as a string with its twists in the given notation
+    pub fn to_notation(&self, notation: Notation) -> String {
+        // twists are wrapped in a group so that suffixes aren't read as part of the twist
+        let operand = |node: &AlgorithmNode| match node {
+            AlgorithmNode::Twist(twist) => format!("({})", twist.to_notation(notation)),
+            node => node.to_notation(notation),
+        };
+
+        match self {
+            AlgorithmNode::Twist(twist) => twist.to_notation(notation),
+            AlgorithmNode::Group(alg) => format!("({})", alg.to_notation(notation)),
+            AlgorithmNode::Commutator(a, b) => {
+                format!("[{}, {}]", a.to_notation(notation), b.to_notation(notation))
+            }
+            AlgorithmNode::Conjugate(a, b) => {
+                format!("[{}: {}]", a.to_notation(notation), b.to_notation(notation))
+            }
+            AlgorithmNode::Repeat(node, count) => format!("{}{}", operand(node), count),
+            AlgorithmNode::Inverse(node) => format!("{}'", operand(node)),
+            AlgorithmNode::Comment(comment) => format!("{COMMENT_START} {comment}"),
+            AlgorithmNode::LineBreak => "\n".to_string(),
+        }
+    }
+}
+
+impl Algorithm {
+    /// Returns the twists this algorithm expands to
+    pub fn expand(&self) -> TwistSequence {
+        let mut twists = Vec::new();
+        self.expand_into(&mut twists);
+        TwistSequence(twists)
+    }
+
+    /// Returns the number of twists this algorithm expands to without expanding it
+    fn expanded_len(&self) -> u64 {
+        self.0
+            .iter()
+            .fold(0, |len, node| len.saturating_add(node.expanded_len()))
+    }
+
+    fn expand_into(&self, twists: &mut Vec<Twist>) {
+        for node in &self.0 {
+            node.expand_into(twists);
+        }
+    }
+
+    /// Returns the algorithm as a string with its twists in the given notation
+    pub fn to_notation(&self, notation: Notation) -> String {
+        let mut string = String::new();
+
+        for node in &self.0 {
+            if node == &AlgorithmNode::LineBreak {
+                string.truncate(string.trim_end_matches(' ').len());
+            } else if !string.is_empty() && !string.ends_with('\n') {
+                string.push(' ');
+            }
+
+            string.push_str(&node.to_notation(notation));
+        }
+
+        string
+    }
+}
+
+impl From<TwistSequence> for Algorithm {
+    fn from(value: TwistSequence) -> Self {
+        Algorithm(value.into_iter().map(AlgorithmNode::Twist).collect())
+    }
+}
+
+impl Display for Algorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_notation(Notation::Standard))
+    }
+}
+
+impl std::str::FromStr for Algorithm {
+    type Err = ParseAlgorithmError;
+
+    /// Parses an algorithm, detecting its notation with [`Notation::detect`]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Notation::detect(s).parse_algorithm(s)
+    }
+}
+
+impl Algorithm {
+    /// Parses an algorithm, detecting its notation with [`Notation::detect`], and returns all
+    /// errors with their locations if it is invalid
+    pub fn parse_spanned(s: &str) -> Result<Self, Vec<SpannedParseError>> {
+        Notation::detect(s).parse_algorithm_spanned(s)
+    }
+}
+
+impl Notation {
+    /// Detects the notation of a twist sequence
+    ///
+    /// Sequences with `@` rotations are in Hyperspeedcube notation, sequences without any letters
+    /// are in MC4D notation and all others are in standard notation. Comments are ignored.
+    pub fn detect(s: &str) -> Notation {
+        let twists = s.lines().map(|line| match line.find(COMMENT_START) {
+            Some(pos) => &line[..pos],
+            None => line,
+        });
+
+        let mut has_letters = false;
+        for c in twists.flat_map(str::chars) {
+            if c == '@' {
+                return Notation::Hyperspeedcube;
+            }
+            has_letters |= c.is_alphabetic();
+        }
+
+        if has_letters {
+            Notation::Standard
+        } else {
+            Notation::MC4D
+        }
+    }
+
+    /// Parses an algorithm with its twists in this notation
+    ///
+    /// MC4D twists contain commas so MC4D algorithms are only whitespace separated twists.
+    pub fn parse_algorithm(&self, notation: &str) -> Result<Algorithm, ParseAlgorithmError> {
+        self.parse_algorithm_spanned(notation)
+            .map_err(|errors| errors.into_iter().next().unwrap().error)
+    }
+
+    /// Parses an algorithm with its twists in this notation and returns all errors with their
+    /// locations if it is invalid
+    pub fn parse_algorithm_spanned(
+        &self,
+        notation: &str,
+    ) -> Result<Algorithm, Vec<SpannedParseError>> {
+        AlgorithmParser::new(notation, *self).parse()
+    }
+}
+
+/// Recursive descent parser for algorithms which continues after errors to find all of them
+struct AlgorithmParser<'a> {
+    s: &'a str,
+    pos: usize,
+    notation: Notation,
+    /// Number of twists read so far
+    n_twists: usize,
+    errors: Vec<SpannedParseError>,
+}
+
+impl<'a> AlgorithmParser<'a> {
+    fn new(s: &'a str, notation: Notation) -> Self {
+        AlgorithmParser {
+            s,
+            pos: 0,
+            notation,
+            n_twists: 0,
+            errors: Vec::new(),
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.s[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    /// Returns whether twists may be grouped, which isn't possible in MC4D notation
+    fn has_groups(&self) -> bool {
+        self.notation != Notation::MC4D
+    }
+
+    fn error(&mut self, error: ParseAlgorithmError, span: Range<usize>) {
+        self.errors.push(SpannedParseError {
+            error,
+            span,
+            token_index: self.n_twists,
+            suggestions: Vec::new(),
+        });
+    }
+
+    /// Parses the whole string
+    fn parse(mut self) -> Result<Algorithm, Vec<SpannedParseError>> {
+        let mut nodes = Vec::new();
+
+        loop {
+            nodes.extend(self.parse_sequence().0);
+
+            // the sequence only stops early at a closing character without an opening one
+            match self.bump() {
+                Some(c) => self.error(
+                    ParseAlgorithmError::UnexpectedChar(c),
+                    self.pos - c.len_utf8()..self.pos,
+                ),
+                None => break,
+            }
+        }
+
+        if self.errors.is_empty() {
+            Ok(Algorithm(nodes))
+        } else {
+            Err(self.errors)
+        }
+    }
+
+    /// Reports an error if the node expands to more than [`MAX_EXPANDED_LEN`] twists, replacing it
+    /// with an empty group so the nodes containing it don't report it again
+    fn limit_len(&mut self, node: AlgorithmNode, start: usize) -> AlgorithmNode {
+        if node.expanded_len() <= MAX_EXPANDED_LEN {
+            return node;
+        }
+
+        self.error(ParseAlgorithmError::TooLong, start..self.pos);
+        AlgorithmNode::Group(Algorithm::default())
+    }
+
+    /// Parses nodes until the end of the string or a closing character
+    fn parse_sequence(&mut self) -> Algorithm {
+        let start = self.pos;
+        let mut nodes = Vec::new();
+
+        while let Some(c) = self.peek() {
+            match c {
+                '\n' => {
+                    self.bump();
+                    nodes.push(AlgorithmNode::LineBreak);
+                }
+                c if c.is_whitespace() => {
+                    self.bump();
+                }
+                ')' | ']' | ',' | ':' if self.has_groups() => break,
+                _ if self.has_groups() && self.rest().starts_with(COMMENT_START) => {
+                    let line = self.rest().lines().next().unwrap_or_default();
+                    self.pos += line.len();
+
+                    let comment = line[COMMENT_START.len()..].trim();
+                    nodes.push(AlgorithmNode::Comment(comment.to_string()));
+                }
+                _ => nodes.extend(self.parse_node()),
+            }
+        }
+
+        // each node is short enough, but together they may not be
+        let alg = Algorithm(nodes);
+        if alg.expanded_len() > MAX_EXPANDED_LEN {
+            self.error(ParseAlgorithmError::TooLong, start..self.pos);
+            return Algorithm::default();
+        }
+
+        alg
+    }
+
+    /// Parses a twist or a group followed by any suffixes
+    fn parse_node(&mut self) -> Option<AlgorithmNode> {
+        let start = self.pos;
+
+        let mut node = match self.peek() {
+            Some('(') if self.has_groups() => {
+                self.bump();
+                let alg = self.parse_sequence();
+                self.expect(')', start);
+                AlgorithmNode::Group(alg)
+            }
+            Some('[') if self.has_groups() => {
+                self.bump();
+                let a = self.parse_sequence();
+                let separator = match self.peek() {
+                    Some(c @ (',' | ':')) => {
+                        self.bump();
+                        Some(c)
+                    }
+                    Some(c) => {
+                        self.error(
+                            ParseAlgorithmError::MissingSeparator,
+                            self.pos..self.pos + c.len_utf8(),
+                        );
+                        None
+                    }
+                    None => None,
+                };
+                let b = match separator {
+                    Some(_) => self.parse_sequence(),
+                    None => Algorithm::default(),
+                };
+                self.expect(']', start);
+
+                match separator {
+                    Some(',') => AlgorithmNode::Commutator(a, b),
+                    Some(_) => AlgorithmNode::Conjugate(a, b),
+                    None => AlgorithmNode::Group(a),
+                }
+            }
+            _ => return self.parse_twist().map(AlgorithmNode::Twist),
+        };
+        // inverses keep the length and repetition counts are limited when they are parsed
+        node = self.limit_len(node, start);
+
+        // suffixes
+        loop {
+            match self.peek() {
+                Some('\'') => {
+                    self.bump();
+                    node = AlgorithmNode::Inverse(Box::new(node));
+                }
+                Some(c) if c.is_ascii_digit() => {
+                    let start = self.pos;
+                    let digits = self.take_while(|c| c.is_ascii_digit());
+
+                    let len = node.expanded_len();
+                    let count = digits.parse().ok().filter(|&count: &u32| {
+                        count > 0 && len.saturating_mul(count as u64) <= MAX_EXPANDED_LEN
+                    });
+
+                    match count {
+                        Some(count) => node = AlgorithmNode::Repeat(Box::new(node), count),
+                        None => self.error(
+                            ParseAlgorithmError::InvalidCount(digits.to_string()),
+                            start..self.pos,
+                        ),
+                    }
+                }
+                _ => return Some(node),
+            }
+        }
+    }
+
+    /// Parses a single twist
+    fn parse_twist(&mut self) -> Option<Twist> {
+        let start = self.pos;
+        let mut in_layer_mask = false;
+
+        while let Some(c) = self.peek() {
+            match c {
+                c if c.is_whitespace() => break,
+                _ if !self.has_groups() => (),
+                '{' => in_layer_mask = true,
+                '}' => in_layer_mask = false,
+                _ if in_layer_mask => (),
+                '(' | ')' | '[' | ']' | ',' | ':' => break,
+                _ if self.rest().starts_with(COMMENT_START) => break,
+                _ => (),
+            }
+            self.bump();
+        }
+
+        let twist = &self.s[start..self.pos];
+        if twist.is_empty() {
+            // only reachable with a character that can't start a node
+            let c = self.bump()?;
+            self.error(ParseAlgorithmError::UnexpectedChar(c), start..self.pos);
+            return None;
+        }
+
+        let result = match self.notation.parse_twist(twist) {
+            Ok(twist) => Some(twist),
+            Err(err) => {
+                self.errors.push(SpannedParseError {
+                    error: ParseAlgorithmError::InvalidTwist(twist.to_string(), err),
+                    span: start..self.pos,
+                    token_index: self.n_twists,
+                    suggestions: suggest_twists(twist, self.notation),
+                });
+                None
+            }
+        };
+
+        self.n_twists += 1;
+        result
+    }
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> &'a str {
+        let start = self.pos;
+        while self.peek().is_some_and(&f) {
+            self.bump();
+        }
+        &self.s[start..self.pos]
+    }
+
+    /// Consumes the closing character of the group opened at the given position
+    fn expect(&mut self, closing: char, open: usize) {
+        if self.peek() == Some(closing) {
+            self.bump();
+        } else {
+            self.error(ParseAlgorithmError::MissingClosing(closing), open..open + 1);
+        }
+    }
+}
+
+/// Returns the valid twists in the notation which are closest to the invalid twist
+fn suggest_twists(twist: &str, notation: Notation) -> Vec<String> {
+    // MC4D twists are numbers so there are no likely typos
+    if notation == Notation::MC4D {
+        return Vec::new();
+    }
+
+    let twist = twist.to_uppercase().chars().collect_vec();
+    let max_distance = if twist.len() <= 3 { 1 } else { 2 };
+
+    Layer::VARIANTS
+        .iter()
+        .flat_map(|&layer| {
+            Twist::ALL_TWISTS
+                .into_iter()
+                .map(move |twist| Twist::new(twist.face, twist.direction, layer))
+        })
+        .map(|twist| twist.to_notation(notation))
+        .unique()
+        .map(|candidate| {
+            let distance = edit_distance(&twist, &candidate.chars().collect_vec());
+            (distance, candidate)
+        })
+        .filter(|(distance, _)| *distance <= max_distance)
+        .sorted_by_key(|(distance, _)| *distance)
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// Returns the number of insertions, deletions, substitutions and swaps of adjacent characters
+/// needed to turn one string into the other
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut dist = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in dist.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    dist[0] = (0..=b.len()).collect();
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+
+            dist[i][j] = (dist[i - 1][j] + 1)
+                .min(dist[i][j - 1] + 1)
+                .min(dist[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                dist[i][j] = dist[i][j].min(dist[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    dist[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_algorithm() {
+        for (alg, expanded) in [
+            ("RO UF", "RO UF"),
+            ("(RO UF)3", "RO UF RO UF RO UF"),
+            ("(RO UF)'", "UF' RO'"),
+            ("(RO UF)2'", "UF' RO' UF' RO'"),
+            ("[RO, UF]", "RO UF RO' UF'"),
+            ("[IF: RO UF RO']", "IF RO UF RO' IF'"),
+            (
+                "[IF: [RO, UF]]2",
+                "IF RO UF RO' UF' IF' IF RO UF RO' UF' IF'",
+            ),
+            ("[{2}RO, (UF)2]", "{2}RO UF UF {2}RO' UF' UF'"),
+            ("RO // comment\n(UF)", "RO UF"),
+            ("", ""),
+        ] {
+            assert_eq!(
+                alg.parse::<Algorithm>().unwrap().expand(),
+                twists(expanded),
+                "{alg}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_algorithm_round_trip() {
+        for alg in [
+            "RO UF",
+            "(RO UF)3 [RO, UF]",
+            "[IF: RO UF RI] // conjugate\n(IF)'\n{1-2}RU",
+            "[[RO, UF]: (IF)2']",
+        ] {
+            let parsed = alg.parse::<Algorithm>().unwrap();
+            assert_eq!(parsed.to_string(), alg);
+            assert_eq!(parsed.to_string().parse(), Ok(parsed));
+        }
+    }
+
+    #[test]
+    fn test_parse_algorithm_errors() {
+        use ParseAlgorithmError::*;
+
+        assert_eq!("(RO UF".parse::<Algorithm>(), Err(MissingClosing(')')));
+        assert_eq!("[RO UF]".parse::<Algorithm>(), Err(MissingSeparator));
+        assert_eq!("[RO, UF".parse::<Algorithm>(), Err(MissingClosing(']')));
+        assert_eq!("(RO, UF)".parse::<Algorithm>(), Err(MissingClosing(')')));
+        assert_eq!("RO)".parse::<Algorithm>(), Err(UnexpectedChar(')')));
+        assert_eq!("(RO)0".parse::<Algorithm>(), Err(InvalidCount("0".into())));
+        assert_eq!(
+            "(RO)4000000000".parse::<Algorithm>(),
+            Err(InvalidCount("4000000000".into()))
+        );
+        assert!(matches!(
+            "(RO UX)".parse::<Algorithm>(),
+            Err(InvalidTwist(twist, _)) if twist == "UX"
+        ));
+    }
+
+    #[test]
+    fn test_parse_algorithm_notation() {
+        let twists = Notation::Hyperspeedcube
+            .parse_twist_sequence("@RU {1,2}FR")
+            .unwrap();
+        assert_eq!(
+            Notation::Hyperspeedcube
+                .parse_algorithm("[@RU, {1,2}FR]")
+                .unwrap()
+                .expand(),
+            TwistSequence(vec![
+                twists[0],
+                twists[1],
+                twists[0].inverse(),
+                twists[1].inverse()
+            ])
+        );
+        assert_eq!(
+            "27,1,1 93,-1,3".parse::<Algorithm>().unwrap().expand(),
+            Notation::MC4D
+                .parse_twist_sequence("27,1,1 93,-1,3")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_spanned_parse_errors() {
+        use ParseAlgorithmError::*;
+
+        let errors = Algorithm::parse_spanned("RO (UX IF] RO)").unwrap_err();
+        let errors = errors
+            .iter()
+            .map(|err| (err.error.clone(), err.span.clone(), err.token_index))
+            .collect_vec();
+
+        assert!(
+            matches!(&errors[0], (InvalidTwist(twist, _), span, 1) if twist == "UX" && *span == (4..6))
+        );
+        assert_eq!(errors[1], (MissingClosing(')'), 3..4, 3));
+        assert_eq!(errors[2], (UnexpectedChar(']'), 9..10, 3));
+        assert_eq!(errors[3], (UnexpectedChar(')'), 13..14, 4));
+        assert_eq!(errors.len(), 4);
+
+        assert!(Algorithm::parse_spanned("RO [UF, IF]").is_ok());
+
+        // nested repetitions multiply their counts
+        let errors = Algorithm::parse_spanned("(((RO)1000)1000)1000").unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].error, InvalidCount("1000".into()));
+        assert_eq!(errors[0].span, 16..20);
+        assert!(Algorithm::parse_spanned("((RO)1000)1000").is_ok());
+
+        // nested commutators double in length at each level, so the 19th level is the first to
+        // expand to more than a million twists and the 11 levels around it aren't reported
+        let mut alg = "RO".to_string();
+        for _ in 0..30 {
+            alg = format!("[{alg}, UF]");
+        }
+        let errors = Algorithm::parse_spanned(&alg).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].error, TooLong);
+        assert_eq!(errors[0].span, 11..alg.len() - 11 * ", UF]".len());
+
+        // a sequence of nodes which are each short enough
+        let errors = Algorithm::parse_spanned("(RO)1000000 UF").unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].error, TooLong);
+        assert_eq!(errors[0].span, 0..14);
+        assert!(Algorithm::parse_spanned("(RO)1000000").is_ok());
+    }
+
+    #[test]
+    fn test_twist_suggestions() {
+        let suggestions = |s: &str| {
+            Algorithm::parse_spanned(s).unwrap_err()[0]
+                .suggestions
+                .clone()
+        };
+
+        assert_eq!(suggestions("ro")[0], "RO");
+        assert!(suggestions("RQ").contains(&"RO".to_string()));
+        assert!(suggestions("XO").contains(&"RO".to_string()));
+        assert!(suggestions("RUFL").contains(&"RUF".to_string()));
+        assert!(suggestions("27,1,x").is_empty());
+    }
+
+    #[test]
+    fn test_detect_notation() {
+        assert_eq!(Notation::detect("RO UF"), Notation::Standard);
+        assert_eq!(Notation::detect("27,1,1 93,-1,3"), Notation::MC4D);
+        assert_eq!(Notation::detect("@RU FR"), Notation::Hyperspeedcube);
+        assert_eq!(
+            Notation::detect("27,1,1 // a comment @ here"),
+            Notation::MC4D
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use crate::*;
+
+/// A cycle of pieces in a cube state
+///
+/// The piece at each location moves to the next location, and the piece at the last location moves
+/// to the first. A cycle with a single location is a piece which stays in place but is twisted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PieceCycle {
+    /// The locations in the cycle, starting from the lowest location index
+    pub locations: Vec<PieceLocation>,
+    /// The orientation of the piece at the first location after going around the cycle once
+    pub orientation: A4,
+}
+
+impl PieceCycle {
+    /// Returns the number of times the cycle has to be applied for its pieces to be solved
+    pub fn order(&self) -> usize {
+        self.locations.len() * self.orientation.order()
+    }
+}
+
+impl Cube {
+    /// Returns whether two cube states are the same, optionally ignoring the orientation of the
+    /// whole cube
+    pub fn is_equivalent(&self, other: &Cube, up_to_rotation: bool) -> bool {
+        if up_to_rotation {
+            self.without_rotation() == other.without_rotation()
+        } else {
+            self == other
+        }
+    }
+
+    /// Returns the cycles of pieces which are not solved
+    ///
+    /// The rotation of the cube is removed first so the LDBO piece is never part of a cycle.
+    pub fn cycles(&self) -> Vec<PieceCycle> {
+        let cube = self.without_rotation();
+        let permutation = Permutation::from_cube(cube);
+        let orientation = Orientation::<A4>::from_cube(cube);
+
+        // the location each piece is moved to
+        let destinations = permutation.inverse().into_array();
+
+        let mut visited = [false; 15];
+        let mut cycles = Vec::new();
+
+        for start in 0..15 {
+            if visited[start] {
+                continue;
+            }
+
+            let mut indices = vec![start];
+            visited[start] = true;
+
+            let mut index = destinations[start] as usize;
+            while index != start {
+                indices.push(index);
+                visited[index] = true;
+                index = destinations[index] as usize;
+            }
+
+            // going around the cycle once brings each piece back to its own location
+            let mut power_permutation = Permutation::SOLVED;
+            let mut power_orientation = Orientation::<A4>::SOLVED;
+            for _ in 0..indices.len() {
+                power_orientation = power_orientation
+                    .permute(&permutation)
+                    .apply_orientation(&orientation);
+                power_permutation = power_permutation.permute(&permutation);
+            }
+            debug_assert_eq!(power_permutation.as_array()[start] as usize, start);
+
+            let cycle_orientation = power_orientation.as_array()[start];
+
+            if indices.len() == 1 && cycle_orientation == A4::IDENTITY {
+                continue;
+            }
+
+            cycles.push(PieceCycle {
+                locations: indices.into_iter().map(|i| PieceLocation::ALL[i]).collect(),
+                orientation: cycle_orientation,
+            });
+        }
+
+        cycles
+    }
+}
+
+impl TwistSequence {
+    /// Returns whether two sequences have the same effect on the cube, optionally ignoring the
+    /// orientation of the whole cube
+    pub fn is_equivalent(&self, other: &TwistSequence, up_to_rotation: bool) -> bool {
+        Cube::SOLVED
+            .twists(self.iter().copied())
+            .is_equivalent(&Cube::SOLVED.twists(other.iter().copied()), up_to_rotation)
+    }
+
+    /// Returns the number of times the sequence has to be repeated for the cube to be solved up to
+    /// rotation
+    pub fn order(&self) -> usize {
+        let mut cube = Cube::SOLVED.twists(self.iter().copied());
+        let mut order = 1;
+
+        while !cube.is_solved() {
+            cube = cube.twists(self.iter().copied());
+            order += 1;
+        }
+
+        order
+    }
+
+    /// Returns the cycles of pieces which are not solved after applying the sequence
+    ///
+    /// See [`Cube::cycles`].
+    pub fn cycles(&self) -> Vec<PieceCycle> {
+        Cube::SOLVED.twists(self.iter().copied()).cycles()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle::twists;
+
+    #[test]
+    fn test_is_equivalent() {
+        assert!(twists("RO RO").is_equivalent(&twists("RO2"), false));
+        assert!(twists("[RO, UF]").is_equivalent(&twists("RO UF RI UB"), false));
+        assert!(!twists("RO").is_equivalent(&twists("LO"), false));
+        assert!(twists("RO").is_equivalent(&twists("{1-2}RO LO"), false));
+        assert!(twists("RO").is_equivalent(&twists("LO"), true));
+        assert!(twists("{1-2}UF").is_equivalent(&twists(""), true));
+        assert!(!twists("RO").is_equivalent(&twists("RU"), true));
+        assert!(twists("RO UF {1-2}FR").is_equivalent(&twists("RO UF"), true));
+        assert!(!twists("RO UF {1-2}FR").is_equivalent(&twists("RO UF"), false));
+    }
+
+    #[test]
+    fn test_order() {
+        assert_eq!(twists("").order(), 1);
+        assert_eq!(twists("{1-2}RO").order(), 1);
+        assert_eq!(twists("RO").order(), 4);
+        assert_eq!(twists("RO2").order(), 2);
+        assert_eq!(twists("LO").order(), 4);
+        assert_eq!(twists("RO UF").order() % twists("(RO UF)2").order(), 0);
+    }
+
+    #[test]
+    fn test_cycles() {
+        assert!(twists("").cycles().is_empty());
+        assert!(twists("{1-2}UF {1-2}RO").cycles().is_empty());
+
+        let cycles = twists("RO").cycles();
+        assert_eq!(cycles.len(), 2);
+        assert!(cycles.iter().all(|cycle| cycle.locations.len() == 4));
+        assert_eq!(twists("RO UF {1-2}FR").cycles(), twists("RO UF").cycles());
+
+        // the order of a sequence which doesn't move the LDBO piece is the lcm of its cycle orders
+        for sequence in [
+            "RO UF",
+            "RO UF IR",
+            "RO UF RI UB",
+            "FU2 RO IF",
+            "[RO, UF] IR2",
+        ] {
+            let sequence = twists(sequence);
+            let order = sequence
+                .cycles()
+                .iter()
+                .fold(1, |acc, cycle| lcm(acc, cycle.order()));
+
+            assert_eq!(order, sequence.order(), "{sequence:?}");
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use super::simplify::{twist_from_state, twist_order_key, twists_commute};
+use super::*;
+
+/// Which sequences [`TwistSequence::canonical_form`] treats as the same
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Hash)]
+pub enum SequenceEquivalence {
+    /// Sequences which only differ by the order of commuting twists
+    #[default]
+    Commutation,
+    /// Sequences which differ by the order of commuting twists and a whole cube rotation
+    Rotation,
+    /// Sequences which differ by the order of commuting twists and a rotation or mirror image of
+    /// the whole cube
+    Symmetry,
+}
+
+impl SequenceEquivalence {
+    /// All equivalences
+    pub const ALL: [Self; 3] = [
+        SequenceEquivalence::Commutation,
+        SequenceEquivalence::Rotation,
+        SequenceEquivalence::Symmetry,
+    ];
+
+    /// Returns the name of the equivalence
+    pub const fn name(&self) -> &'static str {
+        match self {
+            SequenceEquivalence::Commutation => "commutation",
+            SequenceEquivalence::Rotation => "rotation",
+            SequenceEquivalence::Symmetry => "symmetry",
+        }
+    }
+}
+
+/// Returns the order of the twists which is first by the key among all orders that only swap
+/// commuting twists, given whether each pair of twists commutes
+fn commuting_order<K: Ord>(keys: &[K], commutes: &[Vec<bool>]) -> Vec<usize> {
+    let mut remaining = (0..keys.len()).collect::<Vec<_>>();
+    let mut order = Vec::with_capacity(keys.len());
+
+    while !remaining.is_empty() {
+        // a twist can be moved to the front if it commutes with every twist before it, which
+        // always includes the first twist
+        let next = (0..remaining.len())
+            .filter(|&i| {
+                remaining[..i]
+                    .iter()
+                    .all(|&before| commutes[remaining[i]][before])
+            })
+            .min_by_key(|&i| &keys[remaining[i]])
+            .expect("the first twist should be able to move to the front");
+
+        order.push(remaining.remove(next));
+    }
+
+    order
+}
+
+impl TwistSequence {
+    /// Returns whether each pair of twists in the sequence commutes
+    fn commutation_table(&self) -> Vec<Vec<bool>> {
+        self.iter()
+            .map(|&a| self.iter().map(|&b| twists_commute(a, b)).collect())
+            .collect()
+    }
+
+    /// Returns the sequence with commuting twists reordered into a canonical order
+    ///
+    /// Sequences which only differ by the order of commuting twists give the same sequence, which
+    /// has the same effect as this sequence.
+    pub fn sort_commuting(&self) -> Self {
+        let keys = self.iter().map(twist_order_key).collect::<Vec<_>>();
+
+        commuting_order(&keys, &self.commutation_table())
+            .into_iter()
+            .map(|i| self[i])
+            .collect()
+    }
+
+    /// Returns a sequence which is the same for all sequences that are equivalent to this one
+    ///
+    /// Twists with the same effect are replaced by the same twist and commuting twists are sorted
+    /// into a canonical order. Up to rotation or symmetry, the result is the first of the
+    /// transformed sequences, so it can have a different effect than this sequence and is only
+    /// meant for comparing sequences.
+    pub fn canonical_form(&self, equivalence: SequenceEquivalence) -> Self {
+        let transformed = match equivalence {
+            SequenceEquivalence::Commutation => vec![self.clone()],
+            SequenceEquivalence::Rotation => Rotation::iter_rotations()
+                .map(|rotation| self.transform_by(rotation))
+                .collect(),
+            SequenceEquivalence::Symmetry => Rotation::iter_rotations()
+                .flat_map(|rotation| {
+                    let rotated = self.transform_by(rotation);
+                    [rotated.mirror(Axis::X), rotated]
+                })
+                .collect(),
+        };
+
+        // transforming the whole cube keeps the same pairs of twists commuting
+        let commutes = self.commutation_table();
+
+        transformed
+            .into_iter()
+            .map(|sequence| {
+                let twists = sequence
+                    .iter()
+                    .map(|&twist| twist_from_state(&Cube::SOLVED.twist(twist)).unwrap_or(twist))
+                    .collect::<Vec<_>>();
+                let keys = twists.iter().map(twist_order_key).collect::<Vec<_>>();
+
+                commuting_order(&keys, &commutes)
+                    .into_iter()
+                    .map(|i| (keys[i], twists[i]))
+                    .collect::<Vec<_>>()
+            })
+            .min_by(|a, b| {
+                let key = |sequence: &Vec<((u8, u8, u8), Twist)>| {
+                    sequence.iter().map(|(key, _)| *key).collect::<Vec<_>>()
+                };
+                key(a).cmp(&key(b))
+            })
+            .expect("there should be at least one transformed sequence")
+            .into_iter()
+            .map(|(_, twist)| twist)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_commuting() {
+        let sequence = twists("RO LO UF IF2 RU");
+        let sorted = sequence.sort_commuting();
+
+        assert_eq!(sorted, twists("LO RO UF IF2 RU").sort_commuting());
+        assert_ne!(sorted, twists("UF RO LO IF2 RU").sort_commuting());
+        assert_eq!(
+            Cube::SOLVED.twists(sorted),
+            Cube::SOLVED.twists(sequence.iter().copied())
+        );
+    }
+
+    #[test]
+    fn test_canonical_form() {
+        let sequence = twists("RO LO UF IF2 RU");
+        let rotation = Rotation::iter_rotations().nth(7).unwrap();
+        let rotated = twists("LO RO UF IF2 RU").transform_by(rotation);
+        let mirrored = sequence.mirror(Axis::Y);
+
+        for equivalence in SequenceEquivalence::ALL {
+            let canonical = sequence.canonical_form(equivalence);
+
+            assert_eq!(
+                twists("LO RO UF IF2 RU").canonical_form(equivalence),
+                canonical
+            );
+            assert_eq!(
+                rotated.canonical_form(equivalence) == canonical,
+                equivalence != SequenceEquivalence::Commutation
+            );
+            assert_eq!(
+                mirrored.canonical_form(equivalence) == canonical,
+                equivalence == SequenceEquivalence::Symmetry
+            );
+        }
+    }
+}
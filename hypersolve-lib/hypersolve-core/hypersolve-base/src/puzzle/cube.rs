@@ -3,8 +3,7 @@ use const_for::const_for;
 use crate::*;
 
 /// 2<sup>4</sup> cube state
-#[derive(Copy, Clone, PartialEq, Eq, Hash)]
-#[cfg_attr(test, derive(Debug))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct Cube {
     pieces: [Piece; 16],
 }
@@ -15,6 +14,12 @@ impl Default for Cube {
     }
 }
 
+impl std::fmt::Display for Cube {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_facelet_string())
+    }
+}
+
 impl std::ops::Index<PieceLocation> for Cube {
     type Output = Piece;
     fn index(&self, index: PieceLocation) -> &Self::Output {
@@ -40,10 +45,15 @@ impl Cube {
         PieceLocation::ALL[i].solved_piece()
     }));
 
-    const fn new(pieces: [Piece; 16]) -> Cube {
+    pub(crate) const fn new(pieces: [Piece; 16]) -> Cube {
         Cube { pieces }
     }
 
+    /// Returns the pieces of the cube in order of their solved location index
+    pub const fn pieces(&self) -> &[Piece; 16] {
+        &self.pieces
+    }
+
     /// Returns whether the cube is solved
     pub fn is_solved(&self) -> bool {
         let respositioned = self.reposition();
@@ -84,6 +94,30 @@ impl Cube {
         self
     }
 
+    /// Returns the cube which doing the twists of this cube and then the twists of the other gives
+    pub fn then(&self, other: Cube) -> Self {
+        Cube::new(self.pieces.map(|piece| {
+            // each sticker moves to where the other cube moves the sticker which is solved there
+            let moved = other.pieces[piece.current_location().index().into_u8() as usize];
+            Piece::new(piece.faces.map(|face| moved.faces[face.axis() as usize]))
+        }))
+    }
+
+    /// Returns the cube which undoes the twists of this cube
+    pub fn inverse(&self) -> Self {
+        let mut pieces = self.pieces;
+
+        // each sticker moves back to where it is solved
+        for (piece, solved) in self.pieces.iter().zip(Self::SOLVED.pieces) {
+            let inverse = &mut pieces[piece.current_location().index().into_u8() as usize];
+            for (face, solved_face) in piece.faces.iter().zip(solved.faces) {
+                inverse.faces[face.axis() as usize] = solved_face;
+            }
+        }
+
+        Cube::new(pieces)
+    }
+
     pub const fn pieces_except_last(&self) -> [Piece; 15] {
         const_arr!([Piece; 15], |i| self.pieces[i])
     }
@@ -168,6 +202,7 @@ impl Cube {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::puzzle::twists;
 
     #[test]
     fn test_is_solved() {
@@ -178,4 +213,20 @@ mod test {
             )
         }
     }
+
+    #[test]
+    fn test_then_and_inverse() {
+        let first = twists("RO {1-2}UF IF' LD2 {1-2}RU");
+        let second = twists("UB2 {1-2}OU FL' RO");
+        let cube = Cube::SOLVED.twists(first.clone());
+
+        assert_eq!(
+            cube.then(Cube::SOLVED.twists(second.clone())),
+            cube.twists(second)
+        );
+        assert_eq!(cube.inverse(), Cube::SOLVED.twists(first.inverse()));
+        assert_eq!(cube.then(cube.inverse()), Cube::SOLVED);
+        assert_eq!(cube.inverse().then(cube), Cube::SOLVED);
+        assert_eq!(Cube::SOLVED.inverse(), Cube::SOLVED);
+    }
 }
@@ -0,0 +1,244 @@
+use crate::*;
+use itertools::Itertools;
+
+/// Number of stickers on the cube (8 cells with 8 stickers each)
+pub const N_FACELETS: usize = 64;
+
+/// Number of stickers on each cell
+const N_FACELETS_PER_CELL: usize = 8;
+
+const AXES: [Axis; 4] = [Axis::X, Axis::Y, Axis::Z, Axis::W];
+
+/// Errors for creating a cube from its facelets
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FaceletError {
+    #[error("expected {N_FACELETS} facelets but found {0}")]
+    InvalidLength(usize),
+    #[error("invalid facelet color `{0}`")]
+    InvalidColor(char),
+    #[error("stickers at `{0}` do not form a piece")]
+    InvalidPiece(String),
+    #[error("piece `{0}` appears more than once")]
+    DuplicatePiece(String),
+    #[error("piece `{0}` is mirrored")]
+    MirroredPiece(String),
+    #[error("pieces have an odd permutation")]
+    OddPermutation,
+    #[error("pieces are twisted in an impossible way")]
+    InvalidOrientation,
+}
+
+/// Returns the index of the facelet on the `cell` face at the given piece location
+///
+/// Within a cell the stickers are ordered using the basis faces of the cell: back layer
+/// before front layer, top row before bottom row, and left column before right column.
+pub(crate) fn facelet_index(cell: Face, location: PieceLocation) -> usize {
+    let [x, y, z] = cell
+        .basis_faces()
+        .map(|basis_face| location[basis_face.axis()] * basis_face.sign());
+
+    let position = z.to_binary() ^ 1;
+    let position = position * 2 + y.to_binary();
+    let position = position * 2 + (x.to_binary() ^ 1);
+
+    cell as usize * N_FACELETS_PER_CELL + position
+}
+
+/// Returns whether the piece is a rotation (not a reflection) of the piece solved at `home`
+fn is_rotated_piece(piece: &Piece, home: PieceLocation) -> bool {
+    // The sticker from axis `a` is moved from `home[a] * a` to `piece[a]`
+    let axis_map = AXES.map(|axis| piece[axis].axis() as u8);
+    let signs = AXES.map(|axis| home[axis] * piece[axis].sign());
+
+    let Some(axis_permutation) = GenericPermutation::from_array(axis_map) else {
+        return false;
+    };
+
+    let determinant = match axis_permutation.parity() {
+        Parity::Even => Sign::product(signs),
+        Parity::Odd => -Sign::product(signs),
+    };
+
+    determinant == Sign::Pos
+}
+
+impl Cube {
+    /// Returns the face each sticker belongs to, ordered by the cell it is on
+    ///
+    /// Cells are ordered `R`, `L`, `U`, `D`, `F`, `B`, `O`, `I` and each contains 8 stickers.
+    pub fn facelets(&self) -> [Face; N_FACELETS] {
+        let mut facelets = [Face::R; N_FACELETS];
+
+        for (piece, home) in self.pieces().iter().zip(PieceLocation::ALL) {
+            let location = piece.current_location();
+
+            for axis in AXES {
+                facelets[facelet_index(piece[axis], location)] =
+                    Face::from_axis_sign(axis, home[axis]);
+            }
+        }
+
+        facelets
+    }
+
+    /// Returns the solved location of the piece each sticker belongs to, ordered like
+    /// [`facelets()`](#method.facelets)
+    pub fn facelet_pieces(&self) -> [PieceLocation; N_FACELETS] {
+        let mut pieces = [PieceLocation::default(); N_FACELETS];
+
+        for (piece, home) in self.pieces().iter().zip(PieceLocation::ALL) {
+            let location = piece.current_location();
+
+            for axis in AXES {
+                pieces[facelet_index(piece[axis], location)] = home;
+            }
+        }
+
+        pieces
+    }
+
+    /// Creates a cube from the face each sticker belongs to
+    ///
+    /// This is the inverse of [`facelets()`](#method.facelets). The state is checked to be
+    /// reachable by twisting the cube.
+    pub fn from_facelets(facelets: &[Face; N_FACELETS]) -> Result<Self, FaceletError> {
+        let mut pieces: [Option<Piece>; 16] = [None; 16];
+
+        for location in PieceLocation::ALL {
+            let mut piece = Piece::default();
+            let mut home = PieceLocation::default();
+            let mut found_axes = [false; 4];
+
+            for axis in AXES {
+                let cell = Face::from_axis_sign(axis, location[axis]);
+                let color = facelets[facelet_index(cell, location)];
+
+                if std::mem::replace(&mut found_axes[color.axis() as usize], true) {
+                    return Err(FaceletError::InvalidPiece(location.to_string()));
+                }
+
+                home[color.axis()] = color.sign();
+                piece[color.axis()] = cell;
+            }
+
+            if !is_rotated_piece(&piece, home) {
+                return Err(FaceletError::MirroredPiece(home.to_string()));
+            }
+
+            let slot = &mut pieces[home.index().into_u8() as usize];
+            if slot.replace(piece).is_some() {
+                return Err(FaceletError::DuplicatePiece(home.to_string()));
+            }
+        }
+
+        // every location contains a unique piece so all 16 pieces were found
+        let cube = Cube::new(pieces.map(|piece| piece.unwrap()));
+
+        if Permutation::from_cube(cube).0.parity().is_odd() {
+            return Err(FaceletError::OddPermutation);
+        }
+
+        // the orientation of the last piece is determined by the others
+        let orientation = Orientation::<C3>::from_cube(cube);
+        // SAFTEY: c3_coord is always less than N_C3_COORD_STATES
+        if unsafe { Orientation::from_c3_coord(orientation.c3_coord()) } != orientation {
+            return Err(FaceletError::InvalidOrientation);
+        }
+
+        Ok(cube)
+    }
+
+    /// Returns the facelets as a string of face symbols with the cells separated by spaces
+    pub fn to_facelet_string(&self) -> String {
+        self.facelets()
+            .chunks(N_FACELETS_PER_CELL)
+            .map(|cell| cell.iter().map(|face| face.symbol_upper_str()).join(""))
+            .join(" ")
+    }
+
+    /// Creates a cube from a string of face symbols, ignoring whitespace
+    pub fn from_facelet_string(s: &str) -> Result<Self, FaceletError> {
+        let faces = s
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| {
+                Face::from_symbol_upper_str(c.to_string().as_str())
+                    .ok_or(FaceletError::InvalidColor(c))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let facelets = <[Face; N_FACELETS]>::try_from(faces)
+            .map_err(|faces| FaceletError::InvalidLength(faces.len()))?;
+
+        Self::from_facelets(&facelets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solved_facelets() {
+        assert_eq!(
+            Cube::SOLVED.to_facelet_string(),
+            "RRRRRRRR LLLLLLLL UUUUUUUU DDDDDDDD FFFFFFFF BBBBBBBB OOOOOOOO IIIIIIII"
+        );
+    }
+
+    #[test]
+    fn test_facelets_round_trip() {
+        let mut cube = Cube::SOLVED;
+
+        for twist in Twist::ALL_TWISTS {
+            for layer in [Layer::This, Layer::Other, Layer::Both] {
+                cube = cube.twist(Twist::new(twist.face, twist.direction, layer));
+
+                assert_eq!(Cube::from_facelets(&cube.facelets()), Ok(cube));
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_facelets() {
+        let cube = Cube::SOLVED.twists([
+            Twist::new(Face::R, TwistDirection::U, Layer::This),
+            Twist::new(Face::I, TwistDirection::UFR, Layer::This),
+        ]);
+        let facelets = cube.facelets();
+
+        // twist a single piece in place
+        let location = PieceLocation::ALL[3];
+        let [a, b, c, d] =
+            AXES.map(|axis| facelet_index(Face::from_axis_sign(axis, location[axis]), location));
+        let mut twisted = facelets;
+        (twisted[a], twisted[b], twisted[c]) = (facelets[b], facelets[c], facelets[a]);
+        assert_eq!(
+            Cube::from_facelets(&twisted),
+            Err(FaceletError::InvalidOrientation)
+        );
+
+        // mirror a single piece in place
+        let mut mirrored = facelets;
+        mirrored.swap(a, b);
+        assert!(matches!(
+            Cube::from_facelets(&mirrored),
+            Err(FaceletError::MirroredPiece(_))
+        ));
+
+        // put two stickers from the same axis on a piece
+        let mut invalid = facelets;
+        invalid[d] = facelets[facelet_index(Face::R, PieceLocation::ALL[0])];
+        invalid[a] = facelets[facelet_index(Face::L, PieceLocation::ALL[1])];
+        assert!(Cube::from_facelets(&invalid).is_err());
+
+        assert_eq!(
+            Cube::from_facelet_string("RRRR"),
+            Err(FaceletError::InvalidLength(4))
+        );
+        assert_eq!(
+            Cube::from_facelet_string(&Cube::SOLVED.to_facelet_string().replace('R', "X")),
+            Err(FaceletError::InvalidColor('X'))
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use std::fmt::Display;
+
+use itertools::Itertools;
+
+use super::notation::parse_mc4d_twist_string;
+use super::*;
+
+/// Magic word at the start of every MC4D log file
+const MC4D_LOG_MAGIC: &str = "MagicCube4D";
+
+/// Version of the MC4D log file format
+const MC4D_LOG_VERSION: &str = "3";
+
+/// Schläfli symbol of the hypercube
+const MC4D_LOG_SCHLAFLI: &str = "{4,3,3}";
+
+/// Marker separating the scramble from the solution
+const MC4D_LOG_SCRAMBLE_MARKER: &str = "m|";
+
+/// Marker at the end of the twists
+const MC4D_LOG_END: char = '.';
+
+/// Scramble state written to the header of a log with a scramble (a full scramble)
+const MC4D_LOG_SCRAMBLED: u8 = 2;
+
+/// Errors for parsing MC4D log files
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum ParseMC4DLogError {
+    #[error("missing header")]
+    MissingHeader,
+    #[error("invalid header `{0}`")]
+    InvalidHeader(String),
+    #[error("unsupported log version `{0}`, expected version {MC4D_LOG_VERSION}")]
+    UnsupportedVersion(String),
+    #[error("log is for the `{0}` puzzle but only the 2^4 ({MC4D_LOG_SCHLAFLI} with length 2) is supported")]
+    WrongPuzzle(String),
+    #[error("missing `*` after the view matrix")]
+    MissingViewMatrixEnd,
+    #[error("invalid twist `{0}`: {1}")]
+    InvalidTwist(String, ParseMC4DTwistError),
+    #[error("unexpected `{0}` after the end of the log")]
+    UnexpectedValue(String),
+    #[error("missing `{MC4D_LOG_END}` at the end of the log")]
+    MissingEnd,
+}
+
+/// The twists stored in an MC4D log file of the 2<sup>4</sup>
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct MC4DLog {
+    /// Twists made by the scrambler
+    pub scramble: TwistSequence,
+    /// Twists made after scrambling
+    pub solution: TwistSequence,
+}
+
+impl MC4DLog {
+    /// Creates a new log from the scramble and the twists made after it
+    pub fn new(scramble: TwistSequence, solution: TwistSequence) -> Self {
+        MC4DLog { scramble, solution }
+    }
+}
+
+impl std::str::FromStr for MC4DLog {
+    type Err = ParseMC4DLogError;
+
+    /// Parses an MC4D log file
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use ParseMC4DLogError::*;
+
+        let mut lines = s.lines();
+
+        // header: magic, version, scramble state, number of twists, puzzle, and edge length
+        let header = lines.next().ok_or(MissingHeader)?;
+        let fields = header.split_whitespace().collect_vec();
+
+        let [magic, version, _scramble_state, _n_twists, schlafli, length] = fields[..] else {
+            return Err(InvalidHeader(header.to_string()));
+        };
+
+        if magic != MC4D_LOG_MAGIC {
+            return Err(InvalidHeader(header.to_string()));
+        }
+
+        if version != MC4D_LOG_VERSION {
+            return Err(UnsupportedVersion(version.to_string()));
+        }
+
+        if schlafli != MC4D_LOG_SCHLAFLI || length.parse::<f32>() != Ok(2.0) {
+            return Err(WrongPuzzle(format!("{} {}", schlafli, length)));
+        }
+
+        // skip the view matrix
+        if !lines.any(|line| line.trim() == "*") {
+            return Err(MissingViewMatrixEnd);
+        }
+
+        let mut log = MC4DLog::default();
+        let mut twists = Vec::new();
+        let mut tokens = lines.flat_map(str::split_whitespace);
+
+        for token in tokens.by_ref() {
+            if token == MC4D_LOG_SCRAMBLE_MARKER {
+                log.scramble = TwistSequence(std::mem::take(&mut twists));
+                continue;
+            }
+
+            let (twist, end) = match token.strip_suffix(MC4D_LOG_END) {
+                Some(twist) => (twist, true),
+                None => (token, false),
+            };
+
+            if !twist.is_empty() {
+                twists.push(
+                    parse_mc4d_twist_string(twist)
+                        .map_err(|err| InvalidTwist(twist.to_string(), err))?,
+                );
+            }
+
+            if end {
+                log.solution = TwistSequence(twists);
+
+                return match tokens.next() {
+                    Some(token) => Err(UnexpectedValue(token.to_string())),
+                    None => Ok(log),
+                };
+            }
+        }
+
+        Err(MissingEnd)
+    }
+}
+
+impl Display for MC4DLog {
+    /// Formats the log as an MC4D log file
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let scramble = Notation::MC4D.format_twist_sequence(&self.scramble);
+        let solution = Notation::MC4D.format_twist_sequence(&self.solution);
+
+        let n_twists = scramble.split_whitespace().count() + solution.split_whitespace().count();
+        let scramble_state = if self.scramble.is_empty() {
+            0
+        } else {
+            MC4D_LOG_SCRAMBLED
+        };
+
+        writeln!(
+            f,
+            "{MC4D_LOG_MAGIC} {MC4D_LOG_VERSION} {scramble_state} {n_twists} {MC4D_LOG_SCHLAFLI} 2"
+        )?;
+
+        // identity view matrix
+        for i in 0..4 {
+            writeln!(
+                f,
+                "{}",
+                (0..4).map(|j| if i == j { "1.0" } else { "0.0" }).join(" ")
+            )?;
+        }
+        writeln!(f, "*")?;
+
+        if !self.scramble.is_empty() {
+            writeln!(f, "{} {MC4D_LOG_SCRAMBLE_MARKER}", scramble)?;
+        }
+
+        writeln!(f, "{}{MC4D_LOG_END}", solution)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mc4d_log_round_trip() {
+        let scramble: TwistSequence = "RO IUF {2}BDR {1-2}UF IF".parse().unwrap();
+        let solution: TwistSequence = "RO' LDBO UR {1-2}FU".parse().unwrap();
+
+        for log in [
+            MC4DLog::new(scramble.clone(), solution.clone()),
+            MC4DLog::new(scramble, TwistSequence(Vec::new())),
+            MC4DLog::new(TwistSequence(Vec::new()), solution),
+            MC4DLog::default(),
+        ] {
+            assert_eq!(log.to_string().parse(), Ok(log));
+        }
+    }
+
+    #[test]
+    fn test_parse_mc4d_log() {
+        let log = "MagicCube4D 3 2 3 {4,3,3} 2
+0.32 0.38 0.86 0.0
+-0.94 0.13 0.29 0.0
+0.0 -0.91 0.40 0.0
+0.0 0.0 0.0 1.0
+*
+124,1,1 m|
+27,1,1
+93,-1,3.
+";
+
+        let expected = MC4DLog::new(
+            Notation::MC4D.parse_twist_sequence("124,1,1").unwrap(),
+            Notation::MC4D
+                .parse_twist_sequence("27,1,1 93,-1,3")
+                .unwrap(),
+        );
+
+        assert_eq!(log.parse(), Ok(expected));
+
+        assert_eq!(
+            log.replace("{4,3,3} 2", "{4,3,3} 3").parse::<MC4DLog>(),
+            Err(ParseMC4DLogError::WrongPuzzle("{4,3,3} 3".to_string()))
+        );
+        assert_eq!(
+            log.replace("{4,3,3}", "{5,3,3}").parse::<MC4DLog>(),
+            Err(ParseMC4DLogError::WrongPuzzle("{5,3,3} 2".to_string()))
+        );
+        assert_eq!(
+            log.replace("MagicCube4D 3", "MagicCube4D 2")
+                .parse::<MC4DLog>(),
+            Err(ParseMC4DLogError::UnsupportedVersion("2".to_string()))
+        );
+        assert_eq!(
+            log.replace("*", "").parse::<MC4DLog>(),
+            Err(ParseMC4DLogError::MissingViewMatrixEnd)
+        );
+        assert_eq!(
+            log.replace("3.", "3").parse::<MC4DLog>(),
+            Err(ParseMC4DLogError::MissingEnd)
+        );
+        assert!(matches!(
+            log.replace("27,1,1", "27,5,1").parse::<MC4DLog>(),
+            Err(ParseMC4DLogError::InvalidTwist(..))
+        ));
+    }
+}
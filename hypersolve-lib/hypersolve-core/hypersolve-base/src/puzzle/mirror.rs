@@ -0,0 +1,138 @@
+use super::*;
+
+impl Face {
+    /// Returns the face this face is moved to when mirroring the cube across the given axis
+    pub const fn mirror(self, axis: Axis) -> Self {
+        if self.axis() as u8 == axis as u8 {
+            self.opposite()
+        } else {
+            self
+        }
+    }
+}
+
+impl PieceLocation {
+    /// Returns the location this location is moved to when mirroring the cube across the given
+    /// axis
+    pub fn mirror(mut self, axis: Axis) -> Self {
+        self[axis] = -self[axis];
+        self
+    }
+}
+
+impl Cube {
+    /// Returns the mirror image of the cube across the given axis
+    ///
+    /// Each piece is moved to the mirrored location and replaced by the mirror image of the piece
+    /// that is solved there.
+    pub fn mirror(&self, axis: Axis) -> Self {
+        let mut pieces = *self.pieces();
+
+        for (location, piece) in PieceLocation::ALL.into_iter().zip(self.pieces()) {
+            let index = location.mirror(axis).index().into_u8() as usize;
+            pieces[index] = piece.mirror(axis);
+        }
+
+        Cube::new(pieces)
+    }
+}
+
+impl Twist {
+    /// Returns the mirror image of the twist across the given axis
+    ///
+    /// The mirrored twist grips the mirrored face with the same layers and turns around the
+    /// mirrored direction, so doing it on the solved cube gives the mirror image of doing this
+    /// twist. Mirroring a twist twice gives back the same twist.
+    pub fn mirror(&self, axis: Axis) -> Self {
+        let cube = Cube::SOLVED.twist(*self).mirror(axis);
+        let face = self.face.mirror(axis);
+
+        // the vector the twist turns around
+        let mut vector = [0; 4];
+        for (sign, basis) in self
+            .direction
+            .signs_within_face()
+            .into_iter()
+            .zip(self.face.basis())
+        {
+            for i in 0..4 {
+                vector[i] += sign as i32 * basis[i];
+            }
+        }
+        vector[axis as usize] *= -1;
+
+        // mirroring reverses the sense of the twist so it usually turns around the opposite of the
+        // mirrored vector, but the twist direction of the `O` face is also reversed
+        [vector.map(|x| -x), vector]
+            .into_iter()
+            .map(|vector| {
+                let direction = TwistDirection::from_signs_within_face(
+                    face.basis().map(|basis| notation::dot(&vector, &basis)),
+                )
+                .expect("a mirrored twist direction should be within the mirrored face");
+
+                match self.direction.is_double() {
+                    true => direction.double().unwrap(),
+                    false => direction,
+                }
+            })
+            .map(|direction| Twist::new(face, direction, self.layer))
+            .find(|&twist| Cube::SOLVED.twist(twist) == cube)
+            .expect("a mirrored twist should be a twist of the mirrored face")
+    }
+}
+
+impl TwistSequence {
+    /// Returns the mirror image of the sequence across the given axis
+    ///
+    /// See [`Twist::mirror`].
+    pub fn mirror(&self, axis: Axis) -> Self {
+        self.iter().map(|twist| twist.mirror(axis)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AXES: [Axis; 4] = [Axis::X, Axis::Y, Axis::Z, Axis::W];
+
+    #[test]
+    fn test_mirror_twist() {
+        for axis in AXES {
+            for twist in Twist::ALL_TWISTS {
+                for layer in [Layer::This, Layer::Other, Layer::Both] {
+                    let twist = Twist::new(twist.face, twist.direction, layer);
+                    let mirrored = twist.mirror(axis);
+
+                    assert_eq!(mirrored.face, twist.face.mirror(axis));
+                    assert_eq!(mirrored.layer, layer);
+                    assert_eq!(mirrored.mirror(axis), twist);
+                    assert_eq!(
+                        Cube::SOLVED.twist(mirrored),
+                        Cube::SOLVED.twist(twist).mirror(axis)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mirror_sequence() {
+        let sequence: TwistSequence = "RO {1-2}UF IF {2}RU [FR2, UB] LO".parse().unwrap();
+
+        assert_eq!(Cube::SOLVED.mirror(Axis::X), Cube::SOLVED);
+
+        for axis in AXES {
+            let cube = Cube::SOLVED.twists(sequence.iter().copied());
+            let mirrored = sequence.mirror(axis);
+
+            assert_eq!(
+                Cube::SOLVED.twists(mirrored.iter().copied()),
+                cube.mirror(axis)
+            );
+            assert_eq!(cube.mirror(axis).mirror(axis), cube);
+            assert_eq!(mirrored.mirror(axis), sequence);
+        }
+    }
+}
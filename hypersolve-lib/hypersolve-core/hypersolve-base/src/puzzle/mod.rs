@@ -1,18 +1,42 @@
+mod algorithm;
+mod analysis;
 mod axis;
+mod canonical;
 mod cube;
 mod face;
+mod facelet;
+mod mc4d_log;
+mod mirror;
 mod notation;
 mod piece;
+mod rotation;
 mod sign;
+mod simplify;
 mod twist;
 
 pub use piece::*;
 use sign::*;
 
+pub use algorithm::{Algorithm, AlgorithmNode, ParseAlgorithmError, SpannedParseError};
+pub use analysis::PieceCycle;
 pub use axis::Axis;
+pub use canonical::SequenceEquivalence;
 pub use cube::Cube;
 pub use face::Face;
-pub use notation::{Notation, ParseMC4DTwistError, ParseStandardTwistError, ParseTwistError};
-pub use piece::PieceLocation;
+pub use facelet::{FaceletError, N_FACELETS};
+pub use mc4d_log::{MC4DLog, ParseMC4DLogError};
+pub use notation::{
+    Notation, ParseHyperspeedcubeTwistError, ParseMC4DTwistError, ParseStandardTwistError,
+    ParseTwistError,
+};
+pub use piece::{ParsePieceLocationError, PieceLocation};
+pub use rotation::Rotation;
 pub use sign::Sign;
+pub use simplify::RotationMode;
 pub use twist::{Layer, Twist, TwistDirection, TwistSequence};
+
+/// Parses a twist sequence for tests
+#[cfg(test)]
+fn twists(s: &str) -> TwistSequence {
+    s.parse().unwrap()
+}
@@ -12,6 +12,8 @@ pub enum Notation {
     Standard,
     /// Notation used by [MC4D](https://superliminal.com/cube/)
     MC4D,
+    /// Notation used by [Hyperspeedcube](https://github.com/HactarCE/Hyperspeedcube)
+    Hyperspeedcube,
 }
 
 /// Errors for parsing standard twist notation
@@ -54,6 +56,21 @@ pub enum ParseMC4DTwistError {
     UnexpectedValue(String),
 }
 
+/// Errors for parsing Hyperspeedcube twist notation
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ParseHyperspeedcubeTwistError {
+    #[error("invalid twist layer mask `{0}`")]
+    InvalidLayerMask(String),
+    #[error("missing twist face")]
+    MissingFace,
+    #[error("invalid twist face `{0}`")]
+    InvalidFace(String),
+    #[error("missing twist direction")]
+    MissingDirection,
+    #[error("invalid twist direction `{0}`")]
+    InvalidDirection(String),
+}
+
 /// Errors for parsing twist notation
 #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
 #[allow(clippy::upper_case_acronyms)]
@@ -62,6 +79,8 @@ pub enum ParseTwistError {
     Standard(ParseStandardTwistError),
     #[error("MC4D twist notation error: {0}")]
     MC4D(ParseMC4DTwistError),
+    #[error("Hyperspeedcube twist notation error: {0}")]
+    Hyperspeedcube(ParseHyperspeedcubeTwistError),
     #[error("unrecognized twist notation `{0}`")]
     UnrecognizedNotation(String),
 }
@@ -76,6 +95,11 @@ impl From<ParseMC4DTwistError> for ParseTwistError {
         ParseTwistError::MC4D(value)
     }
 }
+impl From<ParseHyperspeedcubeTwistError> for ParseTwistError {
+    fn from(value: ParseHyperspeedcubeTwistError) -> Self {
+        ParseTwistError::Hyperspeedcube(value)
+    }
+}
 
 impl Notation {
     /// Parses a twist from its notation
@@ -83,20 +107,18 @@ impl Notation {
         match self {
             Notation::Standard => Ok(parse_standard_twist_string(notation)?),
             Notation::MC4D => Ok(parse_mc4d_twist_string(notation)?),
+            Notation::Hyperspeedcube => Ok(parse_hyperspeedcube_twist_string(notation)?),
         }
     }
 
     /// Parses a twist sequence from its notation
-    pub fn parse_twist_sequence(&self, notation: &str) -> Result<TwistSequence, ParseTwistError> {
-        let twists = notation.split_whitespace();
-
-        let mut result = Vec::new();
-
-        for twist_string in twists {
-            result.push(self.parse_twist(twist_string)?)
-        }
-
-        Ok(TwistSequence(result))
+    ///
+    /// The twists may be grouped as described in [`Algorithm`].
+    pub fn parse_twist_sequence(
+        &self,
+        notation: &str,
+    ) -> Result<TwistSequence, ParseAlgorithmError> {
+        Ok(self.parse_algorithm(notation)?.expand())
     }
 
     /// Formats the twist according to this notation
@@ -104,6 +126,7 @@ impl Notation {
         match self {
             Notation::Standard => twist_to_standard_string(twist),
             Notation::MC4D => twist_to_mc4d_string(twist),
+            Notation::Hyperspeedcube => twist_to_hyperspeedcube_string(twist),
         }
     }
 
@@ -196,6 +219,59 @@ fn twist_to_mc4d_string(twist: &Twist) -> String {
     format!("{sticker_id},{direction_id},{layer_mask}")
 }
 
+/// Returns the Hyperspeedcube string for this twist
+///
+/// Twists of both layers are whole cube rotations which Hyperspeedcube writes with an `@` prefix
+fn twist_to_hyperspeedcube_string(twist: &Twist) -> String {
+    let prefix = match twist.layer {
+        Layer::This => "",
+        Layer::Other => "{2}",
+        Layer::Both => "@",
+    };
+
+    format!("{}{}{:?}", prefix, twist.face, twist.direction)
+}
+
+/// Creates a twist from its Hyperspeedcube notation
+fn parse_hyperspeedcube_twist_string(mut s: &str) -> Result<Twist, ParseHyperspeedcubeTwistError> {
+    use ParseHyperspeedcubeTwistError::*;
+
+    // Get the twist layer
+    let layer = if let Some(remaining) = s.strip_prefix('@') {
+        s = remaining;
+        Layer::Both
+    } else if let Some(pos) = s.find('}') {
+        let (layer_str, remaining) = s.split_at(pos + 1);
+        s = remaining;
+
+        match layer_str {
+            "{1}" => Layer::This,
+            "{2}" => Layer::Other,
+            "{1,2}" | "{1-2}" | "{1..2}" => Layer::Both,
+            _ => return Err(InvalidLayerMask(layer_str.into())),
+        }
+    } else {
+        Layer::This
+    };
+
+    let mut chars = s.chars();
+
+    let face_str = chars.next().ok_or(MissingFace)?;
+    let face = Face::from_symbol_upper_str(face_str.to_string().as_str())
+        .ok_or(InvalidFace(face_str.into()))?;
+
+    let direction_str = chars.as_str();
+    if direction_str.is_empty() {
+        return Err(MissingDirection);
+    }
+
+    let direction = direction_str
+        .parse::<TwistDirection>()
+        .or(Err(InvalidDirection(direction_str.into())))?;
+
+    Ok(Twist::new(face, direction, layer))
+}
+
 /// Creates a twist from its standard notation
 fn parse_standard_twist_string(mut s: &str) -> Result<Twist, ParseStandardTwistError> {
     // Get the twist layer
@@ -303,7 +379,7 @@ fn parse_standard_twist_string(mut s: &str) -> Result<Twist, ParseStandardTwistE
 }
 
 /// Creates a twist from its MC4D notation
-fn parse_mc4d_twist_string(s: &str) -> Result<Twist, ParseMC4DTwistError> {
+pub(super) fn parse_mc4d_twist_string(s: &str) -> Result<Twist, ParseMC4DTwistError> {
     use once_cell::sync::Lazy;
     use ParseMC4DTwistError::*;
 
@@ -400,7 +476,7 @@ fn mc4d_twist_order() -> impl Iterator<Item = Option<(Face, TwistDirection)>> {
     })
 }
 
-fn dot<const N: usize>(vec1: &[i32; N], vec2: &[i32; N]) -> i32 {
+pub(super) fn dot<const N: usize>(vec1: &[i32; N], vec2: &[i32; N]) -> i32 {
     vec1.iter().zip(vec2.iter()).map(|(a, b)| a * b).sum()
 }
 
@@ -487,6 +563,47 @@ mod tests {
         assert_eq!(twist, expected);
     }
 
+    #[test]
+    fn test_twist_hyperspeedcube_string() {
+        let twist = Twist::new(Face::R, TwistDirection::UF, Layer::This);
+        assert_eq!(twist_to_hyperspeedcube_string(&twist), "RUF");
+
+        let twist = Twist::new(Face::O, TwistDirection::R2, Layer::Other);
+        assert_eq!(twist_to_hyperspeedcube_string(&twist), "{2}OR2");
+
+        let twist = Twist::new(Face::I, TwistDirection::DBL, Layer::Both);
+        assert_eq!(twist_to_hyperspeedcube_string(&twist), "@IDBL");
+
+        assert_eq!(
+            parse_hyperspeedcube_twist_string("{1,2}IDBL"),
+            Ok(Twist::new(Face::I, TwistDirection::DBL, Layer::Both))
+        );
+        assert_eq!(
+            parse_hyperspeedcube_twist_string("RUX"),
+            Err(ParseHyperspeedcubeTwistError::InvalidDirection("UX".into()))
+        );
+        assert_eq!(
+            parse_hyperspeedcube_twist_string("{3}RU"),
+            Err(ParseHyperspeedcubeTwistError::InvalidLayerMask(
+                "{3}".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_twist_to_from_hyperspeedcube_string() {
+        for twist in Twist::ALL_TWISTS {
+            for layer in [Layer::This, Layer::Other, Layer::Both] {
+                let twist = Twist::new(twist.face, twist.direction, layer);
+                assert_eq!(
+                    twist,
+                    parse_hyperspeedcube_twist_string(&twist_to_hyperspeedcube_string(&twist))
+                        .unwrap()
+                )
+            }
+        }
+    }
+
     #[test]
     fn test_twist_to_from_standard_string() {
         Twist::ALL_TWISTS.into_iter().for_each(|twist| {
@@ -138,7 +138,7 @@ impl Piece {
         }
     }
 
-    const fn mirror(mut self, axis: Axis) -> Self {
+    pub(crate) const fn mirror(mut self, axis: Axis) -> Self {
         const_for!(i in 0..self.faces.len() => {
             if self.faces[i].axis() as u8 == axis as u8 {
                 self.faces[i] = self.faces[i].opposite();
@@ -329,6 +329,44 @@ impl PieceLocation {
     }
 }
 
+impl std::str::FromStr for PieceLocation {
+    type Err = ParsePieceLocationError;
+
+    /// Parses a piece location from the faces of the piece solved there, such as `RUFI`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut location = PieceLocation::default();
+        let mut found_axes = [false; 4];
+
+        for c in s.chars() {
+            let face = Face::from_symbol_upper_str(c.to_ascii_uppercase().to_string().as_str())
+                .ok_or(ParsePieceLocationError::InvalidFace(c))?;
+
+            if std::mem::replace(&mut found_axes[face.axis() as usize], true) {
+                return Err(ParsePieceLocationError::RepeatedAxis(s.to_string()));
+            }
+
+            location[face.axis()] = face.sign();
+        }
+
+        if found_axes.contains(&false) {
+            return Err(ParsePieceLocationError::MissingAxis(s.to_string()));
+        }
+
+        Ok(location)
+    }
+}
+
+/// Errors for parsing a piece location
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ParsePieceLocationError {
+    #[error("invalid face `{0}`")]
+    InvalidFace(char),
+    #[error("piece `{0}` contains two faces on the same axis")]
+    RepeatedAxis(String),
+    #[error("piece `{0}` must have a face on every axis")]
+    MissingAxis(String),
+}
+
 /// An index for a piece location
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct PieceLocationIndex(u8);
@@ -394,4 +432,24 @@ mod tests {
             PieceLocation::from_index(PieceLocationIndex(1))
         )
     }
+
+    #[test]
+    fn test_parse_piece_location() {
+        for location in PieceLocation::ALL {
+            assert_eq!(location.to_string().parse(), Ok(location));
+        }
+
+        assert_eq!(
+            "iofr".parse::<PieceLocation>(),
+            Err(ParsePieceLocationError::RepeatedAxis("iofr".to_string()))
+        );
+        assert_eq!(
+            "RUF".parse::<PieceLocation>(),
+            Err(ParsePieceLocationError::MissingAxis("RUF".to_string()))
+        );
+        assert_eq!(
+            "RUFX".parse::<PieceLocation>(),
+            Err(ParsePieceLocationError::InvalidFace('X'))
+        );
+    }
 }
@@ -0,0 +1,328 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Display;
+
+use once_cell::sync::Lazy;
+use strum::VariantArray;
+
+use super::*;
+use crate::GenericPermutation;
+
+/// A rotation of the whole cube
+///
+/// Rotations are the 192 symmetries of the hypercube which preserve its orientation. A rotation is
+/// represented by the faces that the `R`, `U`, `F` and `O` faces are moved to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Rotation {
+    faces: [Face; 4],
+}
+
+impl Default for Rotation {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Display for Rotation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{}{}{}",
+            self.faces[0], self.faces[1], self.faces[2], self.faces[3]
+        )
+    }
+}
+
+/// The shortest sequence of cube rotations for each rotation
+static TWISTS_BY_ROTATION: Lazy<HashMap<Rotation, TwistSequence>> = Lazy::new(|| {
+    let generators = Twist::ALL_TWISTS
+        .iter()
+        .map(|twist| Twist::new(twist.face, twist.direction, Layer::Both))
+        .collect::<Vec<_>>();
+
+    let mut twists = HashMap::from([(Rotation::IDENTITY, TwistSequence::default())]);
+    let mut queue = VecDeque::from([Rotation::IDENTITY]);
+
+    while let Some(rotation) = queue.pop_front() {
+        for &generator in &generators {
+            let next = rotation.then(Rotation::from_twist(generator).unwrap());
+
+            if !twists.contains_key(&next) {
+                let mut sequence = twists[&rotation].clone();
+                sequence.0.push(generator);
+
+                twists.insert(next, sequence);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    twists
+});
+
+impl Rotation {
+    /// The rotation which leaves the cube in place
+    pub const IDENTITY: Self = Self {
+        faces: [Face::R, Face::U, Face::F, Face::O],
+    };
+
+    /// The number of rotations of the cube
+    pub const COUNT: usize = 192;
+
+    /// Returns an iterator over all rotations of the cube
+    pub fn iter_rotations() -> impl Iterator<Item = Self> {
+        const AXES: [Axis; 4] = [Axis::X, Axis::Y, Axis::Z, Axis::W];
+
+        GenericPermutation::<4>::iter_permutations().flat_map(|permutation| {
+            (0..16_u8).filter_map(move |signs| {
+                let sign = |i: usize| match signs >> i & 1 {
+                    0 => Sign::Pos,
+                    _ => Sign::Neg,
+                };
+
+                // mirroring an odd number of axes only preserves the orientation if the axes are
+                // permuted by an odd permutation
+                if permutation.parity().is_odd() != (signs.count_ones() % 2 == 1) {
+                    return None;
+                }
+
+                Some(Rotation {
+                    faces: const_arr!([Face; 4], |i| Face::from_axis_sign(
+                        AXES[permutation.as_array()[i] as usize],
+                        sign(i)
+                    )),
+                })
+            })
+        })
+    }
+
+    /// Returns the rotation done by a twist of both layers, or `None` if the twist is not a
+    /// cube rotation
+    pub const fn from_twist(twist: Twist) -> Option<Self> {
+        if !twist.is_cube_rotation() {
+            return None;
+        }
+
+        // every sticker of the RUFO piece moves to the face its face is rotated to
+        let piece = Piece::new(Self::IDENTITY.faces).twist(twist);
+
+        Some(Rotation { faces: piece.faces })
+    }
+
+    /// Returns the face the given face is moved to
+    pub fn rotate_face(&self, face: Face) -> Face {
+        self.faces[face.axis() as usize] * face.sign()
+    }
+
+    /// Returns the location the given location is moved to
+    pub fn rotate_location(&self, location: PieceLocation) -> PieceLocation {
+        let mut result = location;
+
+        for (i, sign) in location.0.into_iter().enumerate() {
+            let face = self.faces[i] * sign;
+            result[face.axis()] = face.sign();
+        }
+
+        result
+    }
+
+    /// Returns the rotation which does this rotation followed by the other
+    pub fn then(&self, other: Rotation) -> Self {
+        Rotation {
+            faces: self.faces.map(|face| other.rotate_face(face)),
+        }
+    }
+
+    /// Returns the rotation which undoes this rotation
+    pub fn inverse(&self) -> Self {
+        let mut faces = Self::IDENTITY.faces;
+
+        for (face, identity) in self.faces.into_iter().zip(Self::IDENTITY.faces) {
+            faces[face.axis() as usize] = identity * face.sign();
+        }
+
+        Rotation { faces }
+    }
+
+    /// Returns the shortest sequence of cube rotations which does this rotation
+    pub fn to_twists(&self) -> TwistSequence {
+        TWISTS_BY_ROTATION[self].clone()
+    }
+}
+
+impl Cube {
+    /// Rotates the whole cube
+    pub fn rotate(&self, rotation: Rotation) -> Self {
+        Cube::new(
+            self.pieces()
+                .map(|piece| Piece::new(piece.faces.map(|face| rotation.rotate_face(face)))),
+        )
+    }
+
+    /// Returns the rotation which brings the cube into this state from the state with the LDBO
+    /// piece solved
+    pub fn rotation(&self) -> Rotation {
+        let last = self.pieces()[PieceLocation::LAST.index().into_u8() as usize];
+
+        Rotation {
+            faces: const_arr!([Face; 4], |i| last.faces[i] * PieceLocation::LAST.0[i]),
+        }
+    }
+
+    /// Rotates the whole cube so that the LDBO piece is solved
+    ///
+    /// Unlike [`reposition()`](#method.reposition) this only rotates the cube, so two states are
+    /// the same up to rotation exactly when they are the same after removing the rotation.
+    pub fn without_rotation(&self) -> Self {
+        self.rotate(self.rotation().inverse())
+    }
+
+    /// Returns the rotation which turns this cube into the other, or `None` if they are not the
+    /// same up to rotation
+    pub fn rotation_to(&self, other: &Cube) -> Option<Rotation> {
+        if !self.is_equivalent(other, true) {
+            return None;
+        }
+
+        Some(self.rotation().inverse().then(other.rotation()))
+    }
+}
+
+impl Twist {
+    /// Returns the twist which has the same effect in the frame of the cube after the rotation
+    ///
+    /// Doing the transformed twist on the rotated cube is the same as doing this twist and then
+    /// rotating the cube.
+    pub fn transform_by(&self, rotation: Rotation) -> Self {
+        let cube = Cube::SOLVED
+            .rotate(rotation.inverse())
+            .twist(*self)
+            .rotate(rotation);
+        let face = rotation.rotate_face(self.face);
+
+        TwistDirection::VARIANTS
+            .iter()
+            .map(|&direction| Twist::new(face, direction, self.layer))
+            .find(|&twist| Cube::SOLVED.twist(twist) == cube)
+            .expect("a rotated twist should be a twist of the rotated face")
+    }
+}
+
+impl TwistSequence {
+    /// Returns the sequence which has the same effect in the frame of the cube after the rotation
+    ///
+    /// See [`Twist::transform_by`].
+    pub fn transform_by(&self, rotation: Rotation) -> Self {
+        self.iter()
+            .map(|twist| twist.transform_by(rotation))
+            .collect()
+    }
+
+    /// Returns the sequence without cube rotations, rewriting the twists after each rotation so
+    /// that the state is the same up to the orientation of the whole cube
+    pub fn without_rotations(&self) -> Self {
+        let mut rotation = Rotation::IDENTITY;
+        let mut twists = Vec::new();
+
+        for &twist in self.iter() {
+            match Rotation::from_twist(twist) {
+                Some(twist_rotation) => rotation = rotation.then(twist_rotation),
+                None => twists.push(twist.transform_by(rotation.inverse())),
+            }
+        }
+
+        TwistSequence(twists)
+    }
+
+    /// Returns the rotation done by the cube rotations in the sequence
+    pub fn rotation(&self) -> Rotation {
+        self.iter()
+            .filter_map(|&twist| Rotation::from_twist(twist))
+            .fold(Rotation::IDENTITY, |acc, rotation| acc.then(rotation))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_group() {
+        let rotations = Rotation::iter_rotations().collect::<Vec<_>>();
+        assert_eq!(rotations.len(), Rotation::COUNT);
+        assert_eq!(TWISTS_BY_ROTATION.len(), Rotation::COUNT);
+
+        for rotation in rotations {
+            assert_eq!(rotation.then(rotation.inverse()), Rotation::IDENTITY);
+            assert_eq!(
+                Cube::SOLVED.rotate(rotation),
+                Cube::SOLVED.twists(rotation.to_twists())
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_twist() {
+        assert_eq!(Rotation::from_twist(Twist::ALL_TWISTS[0]), None);
+
+        for twist in Twist::ALL_TWISTS {
+            let twist = Twist::new(twist.face, twist.direction, Layer::Both);
+            let rotation = Rotation::from_twist(twist).unwrap();
+
+            assert_eq!(Cube::SOLVED.twist(twist), Cube::SOLVED.rotate(rotation));
+        }
+    }
+
+    #[test]
+    fn test_cube_rotation() {
+        let cube = Cube::SOLVED.twists(twists("RO {1-2}UF IF {1-2}RU"));
+
+        assert_eq!(cube.without_rotation().rotate(cube.rotation()), cube);
+        assert_eq!(
+            cube.without_rotation().pieces()[15],
+            PieceLocation::LAST.solved_piece()
+        );
+
+        let rotation = twists("{1-2}FR {1-2}OU").rotation();
+        assert_eq!(cube.rotation_to(&cube.rotate(rotation)), Some(rotation));
+        assert_eq!(cube.rotation_to(&Cube::SOLVED), None);
+
+        for rotation in Rotation::iter_rotations() {
+            assert!(cube.rotate(rotation).is_equivalent(&cube, true));
+        }
+    }
+
+    #[test]
+    fn test_transform_by() {
+        // rotations are grouped by their 8 sign changes for each axis permutation so every 7th
+        // rotation covers all axis permutations with different signs while keeping the test fast
+        for rotation in Rotation::iter_rotations().step_by(7) {
+            for twist in Twist::ALL_TWISTS {
+                for layer in [Layer::This, Layer::Other, Layer::Both] {
+                    let twist = Twist::new(twist.face, twist.direction, layer);
+                    let transformed = twist.transform_by(rotation);
+
+                    assert_eq!(transformed.layer, layer);
+                    assert_eq!(
+                        Cube::SOLVED.rotate(rotation).twist(transformed),
+                        Cube::SOLVED.twist(twist).rotate(rotation)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_without_rotations() {
+        let sequence = twists("RO {1-2}UF IF {2}RU {1-2}FR2 UB");
+        let stripped = sequence.without_rotations();
+
+        assert!(!stripped.iter().any(Twist::is_cube_rotation));
+        assert_eq!(stripped.len(), 4);
+        assert_eq!(
+            Cube::SOLVED
+                .twists(stripped.iter().copied())
+                .rotate(sequence.rotation()),
+            Cube::SOLVED.twists(sequence)
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+
+use super::*;
+
+/// How [`TwistSequence::simplify_with`] treats whole cube rotations
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Hash)]
+pub enum RotationMode {
+    /// Keep rotations where they are
+    #[default]
+    Keep,
+    /// Move rotations to the end of the sequence by rewriting the twists after them
+    PushToEnd,
+    /// Move rotations to the end of the sequence and remove them, which only preserves the state
+    /// up to the orientation of the whole cube
+    Remove,
+}
+
+/// The canonical single twist with each effect on the solved cube
+///
+/// Twists of the other layer are the same as twists of this layer on the opposite face so only
+/// twists of this layer and rotations are included.
+static TWIST_BY_STATE: Lazy<HashMap<Cube, Twist>> = Lazy::new(|| {
+    let mut twists = HashMap::new();
+
+    for layer in [Layer::This, Layer::Both] {
+        for twist in Twist::ALL_TWISTS {
+            let twist = Twist::new(twist.face, twist.direction, layer);
+            twists.entry(Cube::SOLVED.twist(twist)).or_insert(twist);
+        }
+    }
+
+    twists
+});
+
+/// Returns the single twist which has the given effect on the solved cube
+pub(super) fn twist_from_state(cube: &Cube) -> Option<Twist> {
+    TWIST_BY_STATE.get(cube).copied()
+}
+
+/// Key for the canonical order of commuting twists, with rotations after twists
+pub(super) fn twist_order_key(twist: &Twist) -> (u8, u8, u8) {
+    (twist.layer as u8, twist.face as u8, twist.direction as u8)
+}
+
+/// Returns whether two twists have the same effect in either order
+pub(super) fn twists_commute(a: Twist, b: Twist) -> bool {
+    Cube::SOLVED.twist(a).twist(b) == Cube::SOLVED.twist(b).twist(a)
+}
+
+impl TwistSequence {
+    /// Returns an equivalent sequence without redundant twists
+    ///
+    /// Consecutive twists which cancel are removed, consecutive twists which have the same effect
+    /// as a single twist are merged and commuting twists are sorted into a canonical order (which
+    /// brings together twists that can be merged). Rotations are kept where they are.
+    pub fn simplify(&self) -> Self {
+        self.simplify_with(RotationMode::Keep)
+    }
+
+    /// Returns an equivalent sequence without redundant twists, treating rotations as given
+    ///
+    /// See [`simplify()`](#method.simplify).
+    pub fn simplify_with(&self, rotations: RotationMode) -> Self {
+        let mut twists = self
+            .iter()
+            .map(|&twist| twist_from_state(&Cube::SOLVED.twist(twist)).unwrap_or(twist))
+            .collect::<Vec<_>>();
+
+        loop {
+            if rotations != RotationMode::Keep {
+                twists = push_rotations_to_end(&twists);
+            }
+            if rotations == RotationMode::Remove {
+                twists.retain(|twist| !twist.is_cube_rotation());
+            }
+
+            merge_adjacent_twists(&mut twists);
+
+            // merging twists can create new rotations which need to be pushed again
+            let rotations_at_end = twists
+                .iter()
+                .skip_while(|twist| !twist.is_cube_rotation())
+                .all(Twist::is_cube_rotation);
+
+            if rotations == RotationMode::Keep || rotations_at_end {
+                break;
+            }
+        }
+
+        TwistSequence(twists)
+    }
+}
+
+/// Cancels, merges and sorts adjacent twists until none can be changed
+fn merge_adjacent_twists(twists: &mut Vec<Twist>) {
+    // step back after every change to recheck the new pair before it
+    let mut i = 0;
+    while i + 1 < twists.len() {
+        let (a, b) = (twists[i], twists[i + 1]);
+        let cube = Cube::SOLVED.twist(a).twist(b);
+
+        if cube == Cube::SOLVED {
+            twists.drain(i..i + 2);
+        } else if let Some(twist) = twist_from_state(&cube) {
+            twists.splice(i..i + 2, [twist]);
+        } else if twist_order_key(&b) < twist_order_key(&a) && twists_commute(a, b) {
+            twists.swap(i, i + 1);
+        } else {
+            i += 1;
+            continue;
+        }
+
+        i = i.saturating_sub(1);
+    }
+}
+
+/// Moves the rotations to the end of the twists, rewriting the twists after each rotation so the
+/// state stays the same
+fn push_rotations_to_end(twists: &[Twist]) -> Vec<Twist> {
+    let sequence = TwistSequence(twists.to_vec());
+
+    sequence
+        .without_rotations()
+        .iter()
+        .map(|&twist| {
+            twist_from_state(&Cube::SOLVED.twist(twist))
+                .expect("a twist without rotation should be a twist")
+        })
+        .chain(sequence.iter().copied().filter(Twist::is_cube_rotation))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simplify() {
+        for (sequence, simplified) in [
+            ("RO RI", ""),
+            ("RO RO", "RO2"),
+            ("RO UF UB RI", ""),
+            ("RO LO RI", "LO"),
+            ("LO RO RO", "RO2 LO"),
+            ("RO UF RI", "RO UF RI"),
+            ("RO2 RO2", ""),
+            ("[RO, LU]", ""),
+        ] {
+            assert_eq!(
+                twists(sequence).simplify(),
+                twists(simplified),
+                "{sequence}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_simplify_keeps_state() {
+        let sequence = twists("RO {2}LO UF {1-2}RU IF {2}IF FR {1-2}UF UB2 {1-2}UB OR");
+
+        let simplified = sequence.simplify();
+        assert!(simplified.len() < sequence.len());
+        assert_eq!(
+            Cube::SOLVED.twists(simplified),
+            Cube::SOLVED.twists(sequence.iter().copied())
+        );
+
+        let pushed = sequence.simplify_with(RotationMode::PushToEnd);
+        assert_eq!(
+            Cube::SOLVED.twists(pushed.iter().copied()),
+            Cube::SOLVED.twists(sequence.iter().copied())
+        );
+        assert!(pushed
+            .iter()
+            .skip_while(|twist| !twist.is_cube_rotation())
+            .all(Twist::is_cube_rotation));
+
+        let removed = sequence.simplify_with(RotationMode::Remove);
+        assert!(!removed.iter().any(Twist::is_cube_rotation));
+        assert!(Cube::SOLVED
+            .twists(sequence.inverse())
+            .twists(removed)
+            .is_solved());
+    }
+}
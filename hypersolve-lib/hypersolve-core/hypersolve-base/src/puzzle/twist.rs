@@ -42,7 +42,7 @@ impl Layer {
 /// * [`Face`] determines which face of the hypercube is gripped
 /// * [`TwistDirection`] determines how the 3D slice is twisted
 /// * [`Layer`] determines which layers are gripped
-#[derive(Copy, Clone, PartialEq, Eq, const_gen::CompileConst)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, const_gen::CompileConst)]
 pub struct Twist {
     pub face: Face,
     pub direction: TwistDirection,
@@ -58,22 +58,11 @@ impl Debug for Twist {
 impl std::str::FromStr for Twist {
     type Err = ParseTwistError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let notation = if s
-            .chars()
-            .all(|c| c.is_ascii_punctuation() || c.is_ascii_digit())
-        {
-            // string is likely in MC4D notation
-            Notation::MC4D
-        } else if s
-            .chars()
-            .all(|c| c.is_ascii_alphanumeric() || c.is_ascii_punctuation())
-        {
-            // string is likely in standard notation
-            Notation::Standard
-        } else {
-            // unknown notation
+        if !s.chars().all(|c| c.is_ascii_graphic()) {
             return Err(ParseTwistError::UnrecognizedNotation(s.into()));
-        };
+        }
+
+        let notation = Notation::detect(s);
 
         notation.parse_twist(s)
     }
@@ -438,7 +427,7 @@ impl TwistDirection {
 }
 
 /// A sequence of consecutive twists
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub struct TwistSequence(pub Vec<Twist>);
 
 impl TwistSequence {
@@ -459,18 +448,13 @@ impl TwistSequence {
 }
 
 impl std::str::FromStr for TwistSequence {
-    type Err = ParseTwistError;
+    type Err = ParseAlgorithmError;
 
+    /// Parses a twist sequence, detecting the notation of each twist
+    ///
+    /// The twists may be grouped as described in [`Algorithm`].
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let twists = s.split_whitespace();
-
-        let mut result = Vec::new();
-
-        for twist_string in twists {
-            result.push(Twist::from_str(twist_string)?)
-        }
-
-        Ok(TwistSequence(result))
+        Ok(s.parse::<Algorithm>()?.expand())
     }
 }
 
@@ -1,3 +1,5 @@
+#![allow(clippy::redundant_static_lifetimes)]
+
 use std::marker::PhantomData;
 
 use static_assertions::const_assert_eq;
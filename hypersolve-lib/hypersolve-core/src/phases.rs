@@ -1,7 +1,7 @@
 use crate::*;
 
 /// Solving phases
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PhaseEnum {
     Phase1,
     Phase2,
@@ -35,6 +35,15 @@ impl PhaseEnum {
             PhaseEnum::Phase3 => Phase3::N_MOVES,
         }
     }
+
+    /// Returns God's number for this phase
+    pub const fn max_depth(&self) -> usize {
+        match self {
+            PhaseEnum::Phase1 => Phase1::MAX_DEPTH,
+            PhaseEnum::Phase2 => Phase2::MAX_DEPTH,
+            PhaseEnum::Phase3 => Phase3::MAX_DEPTH,
+        }
+    }
 }
 
 /// Solving phases
@@ -0,0 +1,610 @@
+use std::collections::HashMap;
+use std::ops::RangeBounds;
+use std::sync::Arc;
+
+use hypersolve_core::{
+    CubieCube, Face, Move, MoveIterator, NextMoveIterator, Orientation, Permutation, Phase1,
+    PieceLocation, Rotation, TwistSequence, A4,
+};
+use once_cell::sync::Lazy;
+
+use crate::solution_iterators::length_bounds;
+use crate::Cube;
+
+/// The number of pieces which are moved by the twists of the search, which excludes the LDBO piece
+const N_PIECES: usize = 15;
+
+/// The number of states of a single piece, given by its location and orientation
+const N_PIECE_STATES: usize = N_PIECES * 12;
+
+/// The state each twist of the search takes each state of a single piece to
+///
+/// A piece state is its location index times 12 plus the index of its orientation.
+static PIECE_MOVES: Lazy<Vec<[u8; N_PIECE_STATES]>> = Lazy::new(|| {
+    Move::<Phase1>::iter()
+        .map(|m| {
+            std::array::from_fn(|state| {
+                let mut orientation = [A4::IDENTITY; N_PIECES];
+                // SAFETY: the orientation index is less than 12
+                orientation[state / 12] = unsafe { A4::from_repr_unchecked((state % 12) as u8) };
+
+                let cube = CubieCube {
+                    orientation: Orientation::from_array(orientation),
+                    permutation: Permutation::default(),
+                }
+                .apply_move(m);
+
+                let location = cube
+                    .permutation
+                    .as_array()
+                    .iter()
+                    .position(|&piece| piece as usize == state / 12)
+                    .expect("every piece should have a location");
+
+                (location * 12 + cube.orientation.as_array()[location] as usize) as u8
+            })
+        })
+        .collect()
+});
+
+/// What has to be solved at a piece location to reach a goal
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum PieceGoal {
+    /// Any piece can be at the location in any orientation
+    #[default]
+    Ignore,
+    /// Any piece can be at the location, as long as it is oriented like a piece solved there
+    Orientation,
+    /// The piece solved at the location has to be there in any orientation
+    Permutation,
+    /// The piece solved at the location has to be there in its solved orientation
+    Full,
+}
+
+impl PieceGoal {
+    /// All piece goals
+    pub const ALL: [Self; 4] = [
+        PieceGoal::Ignore,
+        PieceGoal::Orientation,
+        PieceGoal::Permutation,
+        PieceGoal::Full,
+    ];
+
+    /// Returns the name of the goal
+    pub const fn name(&self) -> &'static str {
+        match self {
+            PieceGoal::Ignore => "ignore",
+            PieceGoal::Orientation => "orientation",
+            PieceGoal::Permutation => "permutation",
+            PieceGoal::Full => "full",
+        }
+    }
+}
+
+/// Errors for parsing a goal mask
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ParseGoalMaskError {
+    #[error("expected `<pieces>:<goal>` but found `{0}`")]
+    MissingGoal(String),
+    #[error("no pieces given in `{0}`")]
+    MissingPieces(String),
+    #[error("invalid face `{0}`")]
+    InvalidFace(char),
+    #[error("pieces `{0}` contain two faces on the same axis")]
+    RepeatedAxis(String),
+    #[error("unknown goal `{0}`, valid goals are: ignore, orientation, permutation, full")]
+    UnknownGoal(String),
+}
+
+/// What has to be solved at each piece location for a cube to reach a goal
+///
+/// Goals are checked with the rotation of the cube removed, so the LDBO piece is always solved and
+/// the other pieces are compared relative to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GoalMask([PieceGoal; 16]);
+
+impl Default for GoalMask {
+    fn default() -> Self {
+        GoalMask::IGNORE
+    }
+}
+
+impl std::ops::Index<PieceLocation> for GoalMask {
+    type Output = PieceGoal;
+
+    fn index(&self, index: PieceLocation) -> &Self::Output {
+        &self.0[index.index().into_u8() as usize]
+    }
+}
+
+impl std::ops::IndexMut<PieceLocation> for GoalMask {
+    fn index_mut(&mut self, index: PieceLocation) -> &mut Self::Output {
+        &mut self.0[index.index().into_u8() as usize]
+    }
+}
+
+impl std::str::FromStr for GoalMask {
+    type Err = ParseGoalMaskError;
+
+    /// Parses a goal mask from comma separated `<pieces>:<goal>` entries, such as
+    /// `*:orientation,I:full`
+    ///
+    /// The pieces are either `*` for every piece or up to 4 faces, which select the pieces on all
+    /// of the faces. Later entries override earlier ones and pieces which aren't given are ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut mask = GoalMask::IGNORE;
+
+        for entry in s
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+        {
+            let (pieces, goal) = entry
+                .split_once(':')
+                .ok_or_else(|| ParseGoalMaskError::MissingGoal(entry.to_string()))?;
+
+            let goal = PieceGoal::ALL
+                .into_iter()
+                .find(|g| g.name() == goal.trim().to_ascii_lowercase())
+                .ok_or_else(|| ParseGoalMaskError::UnknownGoal(goal.trim().to_string()))?;
+
+            let pieces = pieces.trim();
+            if pieces.is_empty() {
+                return Err(ParseGoalMaskError::MissingPieces(entry.to_string()));
+            }
+
+            let faces = match pieces {
+                "*" => Vec::new(),
+                _ => pieces
+                    .chars()
+                    .map(|c| {
+                        Face::from_symbol_upper_str(c.to_ascii_uppercase().to_string().as_str())
+                            .ok_or(ParseGoalMaskError::InvalidFace(c))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?,
+            };
+
+            let mut found_axes = [false; 4];
+            for face in &faces {
+                if std::mem::replace(&mut found_axes[face.axis() as usize], true) {
+                    return Err(ParseGoalMaskError::RepeatedAxis(pieces.to_string()));
+                }
+            }
+
+            mask.set_on_faces(&faces, goal);
+        }
+
+        Ok(mask)
+    }
+}
+
+impl GoalMask {
+    /// The goal which every cube reaches
+    pub const IGNORE: Self = GoalMask([PieceGoal::Ignore; 16]);
+
+    /// The goal which only the solved cube reaches
+    pub const SOLVED: Self = GoalMask([PieceGoal::Full; 16]);
+
+    /// Sets the goal of every location which is on all of the faces
+    pub fn set_on_faces(&mut self, faces: &[Face], goal: PieceGoal) {
+        for location in PieceLocation::ALL {
+            if faces
+                .iter()
+                .all(|face| location[face.axis()] == face.sign())
+            {
+                self[location] = goal;
+            }
+        }
+    }
+
+    /// Returns whether the cube reaches the goal
+    pub fn is_reached(&self, cube: &Cube) -> bool {
+        let states = piece_states(CubieCube::from_cube(cube.0.without_rotation()));
+
+        self.piece_conditions()
+            .iter()
+            .zip(states)
+            .all(|(condition, state)| condition.is_met(state))
+    }
+
+    /// Returns the condition each piece has to meet, which the cube meets exactly when every piece
+    /// meets its condition
+    fn piece_conditions(&self) -> [PieceCondition; N_PIECES] {
+        let oriented_at = (0..N_PIECES)
+            .filter(|&location| self.0[location] == PieceGoal::Orientation)
+            .fold(0, |set, location| set | 1 << location);
+
+        std::array::from_fn(|piece| PieceCondition {
+            home: match self.0[piece] {
+                PieceGoal::Ignore | PieceGoal::Orientation => None,
+                PieceGoal::Permutation => Some((piece as u8, false)),
+                PieceGoal::Full => Some((piece as u8, true)),
+            },
+            oriented_at,
+        })
+    }
+}
+
+/// Returns the state of each piece except the LDBO piece, indexed by the location it is solved at
+fn piece_states(cube: CubieCube) -> [u8; N_PIECES] {
+    let mut states = [0; N_PIECES];
+
+    for (location, &piece) in cube.permutation.as_array().iter().enumerate() {
+        states[piece as usize] =
+            (location * 12 + cube.orientation.as_array()[location] as usize) as u8;
+    }
+
+    states
+}
+
+/// What a single piece has to meet for a goal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct PieceCondition {
+    /// The location the piece has to be at and whether it has to be oriented there
+    home: Option<(u8, bool)>,
+    /// The set of locations which the piece has to be oriented at if it is there
+    oriented_at: u16,
+}
+
+impl PieceCondition {
+    /// Returns whether every state of the piece meets the condition
+    fn is_trivial(&self) -> bool {
+        self.home.is_none() && self.oriented_at == 0
+    }
+
+    /// Returns whether the state of the piece meets the condition
+    fn is_met(&self, state: u8) -> bool {
+        let (location, oriented) = (state / 12, state.is_multiple_of(12));
+
+        let at_home = match self.home {
+            None => true,
+            Some((home, must_be_oriented)) => location == home && (oriented || !must_be_oriented),
+        };
+
+        at_home && (oriented || self.oriented_at & 1 << location == 0)
+    }
+}
+
+/// Distances from the states of a few pieces to the nearest states where they meet their
+/// conditions, which are lower bounds on the number of twists needed to reach the goal
+struct PatternTable {
+    /// The indices of the pieces of the table among the pieces of the search
+    pieces: Vec<usize>,
+    depths: Arc<[u8]>,
+}
+
+impl PatternTable {
+    /// Returns the index of the states of the pieces
+    fn index(states: impl IntoIterator<Item = u8>) -> usize {
+        states
+            .into_iter()
+            .fold(0, |index, state| index * N_PIECE_STATES + state as usize)
+    }
+
+    /// Generates the distances for pieces with the conditions with a breadth first search from
+    /// every state that meets the conditions
+    fn generate(conditions: &[PieceCondition]) -> Arc<[u8]> {
+        let n_states = N_PIECE_STATES.pow(conditions.len() as u32);
+        let states_of = |index: usize| {
+            let mut states = vec![0; conditions.len()];
+            let mut rest = index;
+            for state in states.iter_mut().rev() {
+                *state = (rest % N_PIECE_STATES) as u8;
+                rest /= N_PIECE_STATES;
+            }
+            states
+        };
+
+        let mut depths = vec![u8::MAX; n_states];
+        let mut frontier = (0..n_states)
+            .filter(|&index| {
+                let states = states_of(index);
+
+                // two pieces can't be at the same location
+                let distinct = states
+                    .iter()
+                    .enumerate()
+                    .all(|(i, a)| states[..i].iter().all(|b| a / 12 != b / 12));
+
+                distinct
+                    && conditions
+                        .iter()
+                        .zip(&states)
+                        .all(|(condition, &state)| condition.is_met(state))
+            })
+            .collect::<Vec<_>>();
+
+        for &index in &frontier {
+            depths[index] = 0;
+        }
+
+        // the twists are closed under inversion, so searching forwards from the goal gives the
+        // distances to the goal
+        let mut depth = 0;
+        while !frontier.is_empty() {
+            depth += 1;
+            let mut next_frontier = Vec::new();
+
+            for index in frontier {
+                let states = states_of(index);
+
+                for piece_move in PIECE_MOVES.iter() {
+                    let next = Self::index(states.iter().map(|&state| piece_move[state as usize]));
+
+                    if depths[next] == u8::MAX {
+                        depths[next] = depth;
+                        next_frontier.push(next);
+                    }
+                }
+            }
+
+            frontier = next_frontier;
+        }
+
+        depths.into()
+    }
+
+    /// Returns the lower bound for the states of the pieces of the search
+    fn depth(&self, states: &[u8]) -> u8 {
+        self.depths[Self::index(self.pieces.iter().map(|&piece| states[piece]))]
+    }
+}
+
+/// The states of the pieces after some twists of the search and the twists which can follow them
+struct SearchFrame {
+    states: Vec<u8>,
+    next_moves: NextMoveIterator<Phase1, Phase1>,
+}
+
+/// An iterator over all sequences which take a cube to a [`GoalMask`] in order of increasing
+/// length
+///
+/// The search is an IDA* search whose lower bounds come from pattern tables of pairs of pieces,
+/// which are generated for the goal when the iterator is created. Only the pieces which the goal
+/// constrains are tracked, so goals which ignore most of the cube are searched quickly.
+pub struct AlgSearchIterator {
+    /// The conditions of the pieces the goal constrains
+    conditions: Vec<PieceCondition>,
+    tables: Vec<PatternTable>,
+    /// The states of the pieces the goal constrains on the cube being searched
+    start: Vec<u8>,
+    /// The rotation of the cube, which the sequences are transformed by
+    rotation: Rotation,
+    sol_len: usize,
+    sol_len_limit: usize,
+    frames: Vec<SearchFrame>,
+    moves: Vec<Move>,
+    is_first_item: bool,
+}
+
+impl AlgSearchIterator {
+    pub(crate) fn new(
+        cube: hypersolve_core::Cube,
+        goal: &GoalMask,
+        solution_lengths: impl RangeBounds<usize>,
+    ) -> Self {
+        let (min_sol_len, sol_len_limit) = length_bounds(solution_lengths);
+
+        let states = piece_states(CubieCube::from_cube(cube.without_rotation()));
+        let (conditions, start): (Vec<_>, Vec<_>) = goal
+            .piece_conditions()
+            .into_iter()
+            .zip(states)
+            .filter(|(condition, _)| !condition.is_trivial())
+            .unzip();
+
+        // pair each piece with the next one, sharing tables between pairs with the same conditions
+        let groups = match conditions.len() {
+            0 => Vec::new(),
+            1 => vec![vec![0]],
+            n => (1..n).map(|i| vec![i - 1, i]).collect(),
+        };
+
+        let mut generated = HashMap::new();
+        let tables = groups
+            .into_iter()
+            .map(|pieces| {
+                let group_conditions = pieces.iter().map(|&i| conditions[i]).collect::<Vec<_>>();
+                let depths = generated
+                    .entry(group_conditions.clone())
+                    .or_insert_with(|| PatternTable::generate(&group_conditions))
+                    .clone();
+
+                PatternTable { pieces, depths }
+            })
+            .collect();
+
+        Self {
+            conditions,
+            tables,
+            start,
+            rotation: cube.rotation(),
+            sol_len: min_sol_len,
+            sol_len_limit,
+            frames: Vec::new(),
+            moves: Vec::new(),
+            is_first_item: true,
+        }
+    }
+
+    /// Returns a lower bound on the number of twists needed to reach the goal
+    fn depth_bound(&self, states: &[u8]) -> usize {
+        self.tables
+            .iter()
+            .map(|table| table.depth(states) as usize)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns whether the pieces meet their conditions
+    fn is_goal(&self, states: &[u8]) -> bool {
+        self.conditions
+            .iter()
+            .zip(states)
+            .all(|(condition, &state)| condition.is_met(state))
+    }
+
+    /// Returns the sequence of the moves in the frame of the cube being searched
+    fn solution(&self, moves: impl Iterator<Item = Move>) -> TwistSequence {
+        moves
+            .map(|m| *m.twist())
+            .collect::<TwistSequence>()
+            .transform_by(self.rotation)
+    }
+}
+
+impl Iterator for AlgSearchIterator {
+    type Item = TwistSequence;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Some(frame) = self.frames.last_mut() else {
+                // the search of the current length is exhausted, so start the next one
+                if !std::mem::take(&mut self.is_first_item) {
+                    self.sol_len += 1;
+                }
+                if self.sol_len >= self.sol_len_limit {
+                    return None;
+                }
+
+                if self.depth_bound(&self.start) > self.sol_len {
+                    continue;
+                }
+                if self.sol_len == 0 {
+                    if self.is_goal(&self.start) {
+                        return Some(TwistSequence::default());
+                    }
+                    continue;
+                }
+
+                self.frames.push(SearchFrame {
+                    states: self.start.clone(),
+                    next_moves: std::iter::empty().next_moves(false),
+                });
+                continue;
+            };
+
+            let Some(next_move) = frame.next_moves.next() else {
+                self.frames.pop();
+                self.moves.pop();
+                continue;
+            };
+
+            let states = frame
+                .states
+                .iter()
+                .map(|&state| PIECE_MOVES[next_move.into_usize()][state as usize])
+                .collect::<Vec<_>>();
+            let remaining = self.sol_len - self.frames.len();
+
+            if self.depth_bound(&states) > remaining {
+                continue;
+            }
+
+            if remaining == 0 {
+                if self.is_goal(&states) {
+                    return Some(self.solution(self.moves.iter().copied().chain([next_move])));
+                }
+                continue;
+            }
+
+            self.moves.push(next_move);
+            self.frames.push(SearchFrame {
+                states,
+                next_moves: self.moves.iter().copied().next_moves(false),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::twists;
+
+    fn mask(s: &str) -> GoalMask {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_goal_mask() {
+        assert_eq!(mask("*:full"), GoalMask::SOLVED);
+        assert_eq!(mask(""), GoalMask::IGNORE);
+
+        let goal = mask("*:orientation, I:full, RUFI:ignore");
+        assert_eq!(goal["RUFI".parse().unwrap()], PieceGoal::Ignore);
+        assert_eq!(goal["LUFI".parse().unwrap()], PieceGoal::Full);
+        assert_eq!(goal["LUFO".parse().unwrap()], PieceGoal::Orientation);
+
+        assert!(matches!(
+            "I".parse::<GoalMask>(),
+            Err(ParseGoalMaskError::MissingGoal(_))
+        ));
+        assert!(matches!(
+            ":full".parse::<GoalMask>(),
+            Err(ParseGoalMaskError::MissingPieces(_))
+        ));
+        assert_eq!(
+            "X:full".parse::<GoalMask>(),
+            Err(ParseGoalMaskError::InvalidFace('X'))
+        );
+        assert!(matches!(
+            "RL:full".parse::<GoalMask>(),
+            Err(ParseGoalMaskError::RepeatedAxis(_))
+        ));
+        assert!(matches!(
+            "I:solved".parse::<GoalMask>(),
+            Err(ParseGoalMaskError::UnknownGoal(_))
+        ));
+    }
+
+    #[test]
+    fn test_goal_is_reached() {
+        let cube = Cube::SOLVED.twist_seq(twists("IF"));
+
+        assert!(GoalMask::SOLVED.is_reached(&Cube::SOLVED));
+        assert!(!GoalMask::SOLVED.is_reached(&cube));
+        assert!(GoalMask::IGNORE.is_reached(&cube));
+
+        // the twist only moves the pieces of the I cell
+        assert!(mask("O:full").is_reached(&cube));
+        assert!(!mask("I:permutation").is_reached(&cube));
+
+        // a half twist of the I cell swaps pieces without changing their orientation
+        let cube = Cube::SOLVED.twist_seq(twists("IF2"));
+        assert!(mask("*:orientation").is_reached(&cube));
+        assert!(!mask("I:permutation").is_reached(&cube));
+    }
+
+    #[test]
+    fn test_alg_search() {
+        let cube = Cube::SOLVED.twist_seq(twists("RO UF IF"));
+
+        let solutions = AlgSearchIterator::new(cube.0, &GoalMask::SOLVED, ..=3).collect::<Vec<_>>();
+        assert!(!solutions.is_empty());
+        assert!(solutions.iter().all(|solution| solution.len() == 3));
+        assert!(solutions.iter().all(|solution| cube
+            .twist_seq(solution.iter().copied())
+            .is_equivalent(&Cube::SOLVED, true)));
+
+        let goal = mask("O:full");
+        let solutions = AlgSearchIterator::new(cube.0, &goal, ..=3).collect::<Vec<_>>();
+        assert!(!solutions.is_empty());
+        assert!(solutions
+            .windows(2)
+            .all(|pair| pair[0].len() <= pair[1].len()));
+        assert!(solutions
+            .iter()
+            .all(|solution| goal.is_reached(&cube.twist_seq(solution.iter().copied()))));
+    }
+
+    #[test]
+    fn test_alg_search_rotated() {
+        let cube = Cube::SOLVED.twist_seq(twists("{1-2}UF RO UF"));
+        let goal = mask("R:full");
+
+        let solution = AlgSearchIterator::new(cube.0, &goal, ..).next().unwrap();
+        assert!(solution.len() <= 2);
+        assert!(goal.is_reached(&cube.twist_seq(solution)));
+    }
+}
@@ -86,6 +86,7 @@ impl Iterator for UpperBoundIterator {
 pub struct BoundIterator {
     threads: Option<(std::thread::JoinHandle<()>, std::thread::JoinHandle<()>)>,
     work_flag: Arc<AtomicBool>,
+    stop_flag: Arc<AtomicBool>,
     current_bound: Bound<usize>,
     rcv: Receiver<BoundEnum<usize>>,
 }
@@ -95,14 +96,19 @@ impl BoundIterator {
         let (send, rcv) = sync_channel(0);
 
         let work_flag = Arc::new(AtomicBool::new(false));
+        let stop_flag = Arc::new(AtomicBool::new(false));
 
         let c_work_flag = work_flag.clone();
+        let c_stop_flag = stop_flag.clone();
         let c_send = send.clone();
         let lower_bound_thread = std::thread::spawn(move || {
             let mut iter = LowerBoundIterator::new(cube);
 
             loop {
                 while !c_work_flag.load(Ordering::Relaxed) {
+                    if c_stop_flag.load(Ordering::Relaxed) {
+                        return;
+                    }
                     std::thread::park()
                 }
 
@@ -110,21 +116,24 @@ impl BoundIterator {
 
                 let cond = bound.is_exact();
 
-                c_send.send(bound).unwrap();
-
-                if cond {
+                // stop if the iterator was dropped
+                if c_send.send(bound).is_err() || cond {
                     return;
                 }
             }
         });
 
         let c_work_flag = work_flag.clone();
+        let c_stop_flag = stop_flag.clone();
         let c_send = send.clone();
         let upper_bound_thread = std::thread::spawn(move || {
             let mut iter = UpperBoundIterator::new(cube);
 
             loop {
                 while !c_work_flag.load(Ordering::Relaxed) {
+                    if c_stop_flag.load(Ordering::Relaxed) {
+                        return;
+                    }
                     std::thread::park()
                 }
 
@@ -132,9 +141,8 @@ impl BoundIterator {
 
                 let cond = bound.is_exact();
 
-                c_send.send(bound).unwrap();
-
-                if cond {
+                // stop if the iterator was dropped
+                if c_send.send(bound).is_err() || cond {
                     return;
                 }
             }
@@ -143,6 +151,7 @@ impl BoundIterator {
         Self {
             threads: Some((lower_bound_thread, upper_bound_thread)),
             work_flag,
+            stop_flag,
             current_bound: Bound {
                 upper: GODS_NUMBER_UPPER_BOUND,
                 lower: 0,
@@ -184,6 +193,18 @@ impl Iterator for BoundIterator {
     }
 }
 
+impl Drop for BoundIterator {
+    fn drop(&mut self) {
+        // stop the threads once they are done with their current bound so they don't stay parked
+        self.stop_flag.store(true, Ordering::Relaxed);
+
+        if let Some(threads) = &self.threads {
+            threads.0.thread().unpark();
+            threads.1.thread().unpark();
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Bound<T> {
     pub upper: T,
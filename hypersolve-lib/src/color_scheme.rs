@@ -0,0 +1,392 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::*;
+
+/// All faces in order of their index
+const FACES: [Face; 8] = [
+    Face::R,
+    Face::L,
+    Face::U,
+    Face::D,
+    Face::F,
+    Face::B,
+    Face::O,
+    Face::I,
+];
+
+/// Errors for creating a color scheme
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ColorSchemeError {
+    #[error("invalid face `{0}`")]
+    InvalidFace(String),
+    #[error("missing color for face `{0}`")]
+    MissingFace(Face),
+    #[error("invalid color `{0}`, expected a hexadecimal color such as `#ff0000`")]
+    InvalidRgb(String),
+    #[error("invalid symbol `{0}`, symbols cannot be whitespace")]
+    InvalidSymbol(char),
+    #[error("symbol `{0}` is used by more than one face")]
+    DuplicateSymbol(char),
+    #[error("unknown color scheme `{0}`, expected `mc4d` or `hyperspeedcube`")]
+    UnknownPreset(String),
+    #[error("invalid color scheme file: {0}")]
+    InvalidFile(String),
+}
+
+/// A color given by its red, green, and blue components
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Rgb(pub [u8; 3]);
+
+impl Display for Rgb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let [r, g, b] = self.0;
+        write!(f, "#{:02x}{:02x}{:02x}", r, g, b)
+    }
+}
+
+impl FromStr for Rgb {
+    type Err = ColorSchemeError;
+
+    /// Parses a hexadecimal color such as `#ff0000`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ColorSchemeError::InvalidRgb(s.to_string());
+
+        let hex = s.strip_prefix('#').unwrap_or(s);
+        if hex.len() != 6 || !hex.is_ascii() {
+            return Err(invalid());
+        }
+
+        let mut rgb = [0; 3];
+        for (i, channel) in rgb.iter_mut().enumerate() {
+            *channel = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).map_err(|_| invalid())?;
+        }
+
+        Ok(Rgb(rgb))
+    }
+}
+
+impl Rgb {
+    /// Returns the color with each component scaled by the given amount
+    pub fn scale(self, brightness: f64) -> Self {
+        Rgb(self
+            .0
+            .map(|channel| (channel as f64 * brightness).round().clamp(0.0, 255.0) as u8))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Rgb {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Rgb {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// The color of a face
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FaceColor {
+    /// Name of the color
+    pub name: String,
+    /// Symbol representing the color in facelet strings
+    pub symbol: char,
+    /// Color used to draw stickers of the face
+    #[cfg_attr(feature = "serde", serde(rename = "color"))]
+    pub rgb: Rgb,
+}
+
+impl FaceColor {
+    /// Creates a new face color
+    pub fn new(name: impl Into<String>, symbol: char, rgb: [u8; 3]) -> Self {
+        FaceColor {
+            name: name.into(),
+            symbol,
+            rgb: Rgb(rgb),
+        }
+    }
+}
+
+/// A mapping from each face of the cube to a color
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "ColorSchemeFile", into = "ColorSchemeFile")
+)]
+pub struct ColorScheme {
+    name: String,
+    colors: [FaceColor; 8],
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        Self::hyperspeedcube()
+    }
+}
+
+impl FromStr for ColorScheme {
+    type Err = ColorSchemeError;
+
+    /// Returns the preset color scheme with the given name
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mc4d" => Ok(Self::mc4d()),
+            "hyperspeedcube" | "hsc" => Ok(Self::hyperspeedcube()),
+            _ => Err(ColorSchemeError::UnknownPreset(s.to_string())),
+        }
+    }
+}
+
+impl ColorScheme {
+    /// Creates a new color scheme from the colors of the faces in order `R`, `L`, `U`, `D`, `F`,
+    /// `B`, `O`, `I`
+    pub fn new(name: impl Into<String>, colors: [FaceColor; 8]) -> Result<Self, ColorSchemeError> {
+        for (i, color) in colors.iter().enumerate() {
+            if color.symbol.is_whitespace() {
+                return Err(ColorSchemeError::InvalidSymbol(color.symbol));
+            }
+
+            if colors[..i].iter().any(|other| other.symbol == color.symbol) {
+                return Err(ColorSchemeError::DuplicateSymbol(color.symbol));
+            }
+        }
+
+        Ok(ColorScheme {
+            name: name.into(),
+            colors,
+        })
+    }
+
+    /// The default color scheme of Hyperspeedcube
+    pub fn hyperspeedcube() -> Self {
+        ColorScheme {
+            name: "Hyperspeedcube".to_string(),
+            colors: [
+                FaceColor::new("red", 'R', [255, 0, 0]),
+                FaceColor::new("orange", 'O', [255, 128, 0]),
+                FaceColor::new("white", 'W', [255, 255, 255]),
+                FaceColor::new("yellow", 'Y', [255, 255, 0]),
+                FaceColor::new("green", 'G', [0, 255, 0]),
+                FaceColor::new("blue", 'B', [0, 128, 255]),
+                FaceColor::new("purple", 'M', [128, 0, 255]),
+                FaceColor::new("pink", 'P', [255, 128, 255]),
+            ],
+        }
+    }
+
+    /// The default color scheme of MC4D
+    pub fn mc4d() -> Self {
+        ColorScheme {
+            name: "MC4D".to_string(),
+            colors: [
+                FaceColor::new("orange", 'O', [255, 128, 0]),
+                FaceColor::new("red", 'R', [255, 0, 0]),
+                FaceColor::new("white", 'W', [255, 255, 255]),
+                FaceColor::new("yellow", 'Y', [255, 255, 0]),
+                FaceColor::new("blue", 'B', [0, 0, 255]),
+                FaceColor::new("green", 'G', [0, 255, 0]),
+                FaceColor::new("pink", 'P', [255, 128, 255]),
+                FaceColor::new("purple", 'M', [128, 0, 255]),
+            ],
+        }
+    }
+
+    /// Returns the name of the color scheme
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the color of the face
+    pub fn color(&self, face: Face) -> &FaceColor {
+        &self.colors[face as usize]
+    }
+
+    /// Returns the face with the given color symbol
+    pub fn face_from_symbol(&self, symbol: char) -> Option<Face> {
+        FACES
+            .into_iter()
+            .find(|&face| self.color(face).symbol == symbol)
+    }
+
+    /// Parses a color scheme from TOML
+    #[cfg(feature = "serde")]
+    pub fn from_toml_str(s: &str) -> Result<Self, ColorSchemeError> {
+        toml::from_str(s).map_err(|err| ColorSchemeError::InvalidFile(err.message().to_string()))
+    }
+
+    /// Parses a color scheme from JSON
+    #[cfg(feature = "serde")]
+    pub fn from_json_str(s: &str) -> Result<Self, ColorSchemeError> {
+        serde_json::from_str(s).map_err(|err| ColorSchemeError::InvalidFile(err.to_string()))
+    }
+
+    /// Formats the color scheme as TOML
+    #[cfg(feature = "serde")]
+    pub fn to_toml_string(&self) -> String {
+        toml::to_string(self).expect("color schemes are always serializable")
+    }
+
+    /// Formats the color scheme as JSON
+    #[cfg(feature = "serde")]
+    pub fn to_json_string(&self) -> String {
+        serde_json::to_string_pretty(self).expect("color schemes are always serializable")
+    }
+}
+
+/// The serialized form of a color scheme with the colors keyed by face symbol
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ColorSchemeFile {
+    name: String,
+    faces: std::collections::BTreeMap<String, FaceColor>,
+}
+
+#[cfg(feature = "serde")]
+impl From<ColorScheme> for ColorSchemeFile {
+    fn from(value: ColorScheme) -> Self {
+        ColorSchemeFile {
+            name: value.name,
+            faces: FACES
+                .into_iter()
+                .zip(value.colors)
+                .map(|(face, color)| (face.to_string(), color))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<ColorSchemeFile> for ColorScheme {
+    type Error = ColorSchemeError;
+
+    fn try_from(mut value: ColorSchemeFile) -> Result<Self, Self::Error> {
+        if let Some(key) = value
+            .faces
+            .keys()
+            .find(|key| Face::from_symbol_upper_str(key).is_none())
+        {
+            return Err(ColorSchemeError::InvalidFace(key.clone()));
+        }
+
+        let mut colors = Vec::with_capacity(FACES.len());
+        for face in FACES {
+            match value.faces.remove(face.symbol_upper_str()) {
+                Some(color) => colors.push(color),
+                None => return Err(ColorSchemeError::MissingFace(face)),
+            }
+        }
+
+        // there is exactly one color for every face
+        ColorScheme::new(value.name, colors.try_into().unwrap())
+    }
+}
+
+impl Cube {
+    /// Returns the facelets as a string of color symbols with the cells separated by spaces
+    pub fn to_facelet_string_with(&self, scheme: &ColorScheme) -> String {
+        self.facelets()
+            .chunks(8)
+            .map(|cell| {
+                cell.iter()
+                    .map(|&face| scheme.color(face).symbol)
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Creates a cube from a string of color symbols, ignoring whitespace
+    pub fn from_facelet_string_with(s: &str, scheme: &ColorScheme) -> Result<Self, FaceletError> {
+        let faces = s
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| {
+                scheme
+                    .face_from_symbol(c)
+                    .ok_or(FaceletError::InvalidColor(c))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let facelets = <[Face; N_FACELETS]>::try_from(faces)
+            .map_err(|faces| FaceletError::InvalidLength(faces.len()))?;
+
+        Self::from_facelets(&facelets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rgb() {
+        assert_eq!("#ff8000".parse(), Ok(Rgb([255, 128, 0])));
+        assert_eq!("00FF80".parse(), Ok(Rgb([0, 255, 128])));
+        assert_eq!(Rgb([255, 128, 0]).to_string(), "#ff8000");
+        assert!("#ff80".parse::<Rgb>().is_err());
+        assert!("#gg0000".parse::<Rgb>().is_err());
+    }
+
+    #[test]
+    fn test_facelet_string_with() {
+        let cube = Cube::SOLVED.twist_seq("RO IUF".parse::<TwistSequence>().unwrap());
+
+        for scheme in [ColorScheme::mc4d(), ColorScheme::hyperspeedcube()] {
+            let s = cube.to_facelet_string_with(&scheme);
+            assert_eq!(Cube::from_facelet_string_with(&s, &scheme), Ok(cube));
+        }
+
+        assert_eq!(
+            Cube::SOLVED.to_facelet_string_with(&ColorScheme::hyperspeedcube()),
+            "RRRRRRRR OOOOOOOO WWWWWWWW YYYYYYYY GGGGGGGG BBBBBBBB MMMMMMMM PPPPPPPP"
+        );
+    }
+
+    #[test]
+    fn test_invalid_scheme() {
+        let mut colors = ColorScheme::mc4d().colors;
+        colors[1].symbol = colors[0].symbol;
+        assert_eq!(
+            ColorScheme::new("test", colors.clone()),
+            Err(ColorSchemeError::DuplicateSymbol(colors[0].symbol))
+        );
+
+        colors[1].symbol = ' ';
+        assert_eq!(
+            ColorScheme::new("test", colors),
+            Err(ColorSchemeError::InvalidSymbol(' '))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_scheme_files() {
+        for scheme in [ColorScheme::mc4d(), ColorScheme::hyperspeedcube()] {
+            assert_eq!(
+                ColorScheme::from_toml_str(&scheme.to_toml_string()),
+                Ok(scheme.clone())
+            );
+            assert_eq!(
+                ColorScheme::from_json_str(&scheme.to_json_string()),
+                Ok(scheme.clone())
+            );
+        }
+
+        let toml = ColorScheme::mc4d()
+            .to_toml_string()
+            .replace("[faces.I]", "[faces.X]");
+        assert_eq!(
+            ColorScheme::from_toml_str(&toml),
+            Err(ColorSchemeError::InvalidFile(
+                "invalid face `X`".to_string()
+            ))
+        );
+    }
+}
@@ -0,0 +1,227 @@
+use std::collections::VecDeque;
+
+use crate::*;
+
+/// The kind of turn a twist direction makes, which decides how hard the twist is to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TwistClass {
+    /// A 90-degree face twist
+    Quarter,
+    /// A 180-degree face twist
+    Half,
+    /// A 180-degree edge twist
+    Edge,
+    /// A 120-degree corner twist
+    Corner,
+}
+
+impl TwistClass {
+    /// All twist classes
+    pub const ALL: [Self; 4] = [
+        TwistClass::Quarter,
+        TwistClass::Half,
+        TwistClass::Edge,
+        TwistClass::Corner,
+    ];
+
+    /// Returns the class of the twist direction
+    pub const fn of(direction: TwistDirection) -> Self {
+        match direction.dirs_3d().len() {
+            1 => TwistClass::Quarter,
+            _ if direction.is_double() => TwistClass::Half,
+            3 => TwistClass::Edge,
+            _ => TwistClass::Corner,
+        }
+    }
+}
+
+/// Weights for ranking solutions of the same length by how easy they are to do
+///
+/// Each twist costs the sum of the costs of its face, twist class and layer, and each twist which
+/// grips a face on a different axis than the twist before it costs `axis_change` more. Solutions
+/// with a lower total cost are preferred.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CostModel {
+    /// The cost of gripping each face, indexed by [`Face`]
+    pub face: [u32; 8],
+    /// The cost of each class of twist, indexed by [`TwistClass`]
+    pub class: [u32; 4],
+    /// The cost of gripping each layer, in the order [`Layer::This`], [`Layer::Other`] and
+    /// [`Layer::Both`]
+    pub layer: [u32; 3],
+    /// The cost of gripping a face on a different axis than the twist before
+    pub axis_change: u32,
+}
+
+impl CostModel {
+    /// Every solution has no cost, so solutions are only ranked by length
+    pub const UNIFORM: Self = CostModel {
+        face: [0; 8],
+        class: [0; 4],
+        layer: [0; 3],
+        axis_change: 0,
+    };
+
+    /// Preferences for a physical puzzle, where twists of the `I` and `O` faces and whole puzzle
+    /// rotations need gyros and 90-degree twists are the quickest
+    pub const PHYSICAL: Self = CostModel {
+        face: [1, 1, 1, 1, 1, 1, 4, 4],
+        class: [0, 1, 2, 2],
+        layer: [0, 0, 3],
+        axis_change: 1,
+    };
+
+    /// Preferences for a virtual puzzle, where every face is as easy to twist but 90-degree
+    /// twists are the quickest
+    pub const VIRTUAL: Self = CostModel {
+        face: [0; 8],
+        class: [0, 1, 1, 1],
+        layer: [0, 0, 1],
+        axis_change: 0,
+    };
+
+    /// Returns the cost of the twist, without any axis change
+    pub fn twist_cost(&self, twist: Twist) -> u32 {
+        self.face[twist.face as usize]
+            + self.class[TwistClass::of(twist.direction) as usize]
+            + self.layer[twist.layer as usize - 1]
+    }
+
+    /// Returns the total cost of the twists
+    pub fn cost(&self, twists: &[Twist]) -> u32 {
+        let axis_changes = twists
+            .windows(2)
+            .filter(|pair| pair[0].face.axis() != pair[1].face.axis())
+            .count() as u32;
+
+        twists
+            .iter()
+            .map(|&twist| self.twist_cost(twist))
+            .sum::<u32>()
+            + axis_changes * self.axis_change
+    }
+}
+
+/// An iterator over solutions which returns the solutions of each length from the cheapest to the
+/// most expensive by a [`CostModel`]
+///
+/// The solutions of each length are held back until the search finds a longer solution or ends,
+/// so this is only useful for searches which return solutions in order of increasing length.
+/// Solutions with the same cost are returned in the order the search found them.
+pub struct RankedSolutionIterator<I> {
+    solutions: I,
+    cost_model: CostModel,
+    /// The first solution of the next length
+    next_length: Option<TwistSequence>,
+    /// The solutions of the current length which haven't been returned, with their costs
+    ranked: VecDeque<(TwistSequence, u32)>,
+}
+
+impl<I: Iterator<Item = TwistSequence>> RankedSolutionIterator<I> {
+    /// Ranks the solutions of the search
+    pub fn new(solutions: I, cost_model: CostModel) -> Self {
+        RankedSolutionIterator {
+            solutions,
+            cost_model,
+            next_length: None,
+            ranked: VecDeque::new(),
+        }
+    }
+
+    /// Changes the search with the function, discarding the solutions which haven't been
+    /// returned
+    pub fn restart_with(&mut self, restart: impl FnOnce(&mut I)) {
+        self.next_length = None;
+        self.ranked.clear();
+
+        restart(&mut self.solutions);
+    }
+
+    /// Returns the next solution with its cost
+    pub fn next_with_cost(&mut self) -> Option<(TwistSequence, u32)> {
+        if self.ranked.is_empty() {
+            let first = self.next_length.take().or_else(|| self.solutions.next())?;
+
+            let length = first.len();
+            let mut solutions = vec![first];
+
+            for solution in self.solutions.by_ref() {
+                if solution.len() != length {
+                    self.next_length = Some(solution);
+                    break;
+                }
+                solutions.push(solution);
+            }
+
+            let mut ranked = solutions
+                .into_iter()
+                .map(|solution| {
+                    let cost = self.cost_model.cost(&solution);
+                    (solution, cost)
+                })
+                .collect::<Vec<_>>();
+            // the sort is stable so ties keep the order of the search
+            ranked.sort_by_key(|(_, cost)| *cost);
+
+            self.ranked = ranked.into();
+        }
+
+        self.ranked.pop_front()
+    }
+}
+
+impl<I: Iterator<Item = TwistSequence>> Iterator for RankedSolutionIterator<I> {
+    type Item = TwistSequence;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_cost().map(|(solution, _)| solution)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::twists;
+
+    #[test]
+    fn test_twist_class() {
+        for (twist, class) in [
+            ("RO", TwistClass::Quarter),
+            ("RO2", TwistClass::Half),
+            ("UFR", TwistClass::Edge),
+            ("UFRO", TwistClass::Corner),
+        ] {
+            assert_eq!(TwistClass::of(twists(twist)[0].direction), class, "{twist}");
+        }
+    }
+
+    #[test]
+    fn test_cost() {
+        let sequence = twists("RO UF2 IF");
+        assert_eq!(CostModel::UNIFORM.cost(&sequence), 0);
+
+        // faces 1 + 1 + 4, classes 0 + 1 + 0 and changes from X to Y and Y to W
+        assert_eq!(CostModel::PHYSICAL.cost(&sequence), 9);
+
+        assert_eq!(CostModel::PHYSICAL.cost(&twists("RO LO")), 2);
+    }
+
+    #[test]
+    fn test_ranked_solutions() {
+        let solutions = ["RO2 UF", "RO UF", "RO2 UF2", "IF2 RO UF", "RO UF IF"].map(twists);
+
+        let ranked = RankedSolutionIterator::new(solutions.clone().into_iter(), CostModel::VIRTUAL)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            ranked,
+            [
+                &solutions[1],
+                &solutions[0],
+                &solutions[2],
+                &solutions[4],
+                &solutions[3]
+            ]
+            .map(Clone::clone)
+        );
+    }
+}
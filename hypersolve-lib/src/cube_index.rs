@@ -11,6 +11,18 @@ impl CubeIndex {
     pub const SOLVED: Self = CubeIndex(0);
 }
 
+impl std::fmt::Display for CubeIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<CubeIndex> for u128 {
+    fn from(value: CubeIndex) -> Self {
+        value.0
+    }
+}
+
 impl From<CubieCube> for CubeIndex {
     fn from(value: CubieCube) -> Self {
         let phase1_index = Phase1Node::from(value).index() as u128;
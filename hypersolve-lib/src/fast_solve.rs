@@ -1,55 +1,85 @@
 use std::{
     sync::{
-        atomic::{AtomicBool, AtomicUsize, Ordering},
-        mpsc::{sync_channel, Receiver, SyncSender},
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        mpsc::{sync_channel, Receiver, RecvTimeoutError, SyncSender},
         Arc,
     },
     thread::JoinHandle,
+    time::Instant,
 };
 
-use hypersolve_core::{Cube, CubieCube, Face, Layer, Twist, TwistDirection, TwistSequence};
+use hypersolve_core::{Cube, CubieCube, PieceLocation, Rotation, TwistSequence};
 
-use crate::{Node, Phase1Node, Phase2Node, Phase3Node};
+use crate::solution_iterators::TimedOut;
+use crate::{CostModel, Node, Phase1Node, Phase2Node, Phase3Node};
+
+/// Returns the score of a solution, which is lower for better solutions
+///
+/// Solutions are compared by length and then by cost, so the score keeps the length in the high
+/// bits.
+fn solution_score(sol_len: usize, cost: u32) -> u64 {
+    ((sol_len as u64) << 32) | cost as u64
+}
 
 /// Solves the cube on this thread, sending solutions and solution lengths back via `solutions`
+///
+/// Without a cost model only shorter solutions are sent. With a cost model solutions with the same
+/// length are also sent if they are cheaper.
+#[allow(clippy::too_many_arguments)]
 fn fast_solve(
     cube: CubieCube,
     search_flag: Arc<AtomicBool>,
+    stop_flag: Arc<AtomicBool>,
     shortest_sol_length: Arc<AtomicUsize>,
+    best_score: Arc<AtomicU64>,
+    cost_model: Option<CostModel>,
     solutions: SyncSender<(TwistSequence, usize)>,
     pre_sequence: TwistSequence,
 ) {
     // if we should not be searching yet then park the thread
     while !search_flag.load(Ordering::Relaxed) {
+        if stop_flag.load(Ordering::Relaxed) {
+            return;
+        }
         std::thread::park()
     }
 
     let phase1_cube = cube;
 
-    for phase1_sol in Phase1Node::from(cube)
-        .phase_solutions(Vec::new(), ..shortest_sol_length.load(Ordering::Relaxed))
-    {
+    // the exclusive limit on the length of solutions which can be sent
+    let sol_len_limit = || {
+        let shortest = shortest_sol_length.load(Ordering::Relaxed);
+        match cost_model {
+            None => shortest,
+            Some(_) => shortest.saturating_add(1),
+        }
+    };
+
+    for phase1_sol in Phase1Node::from(cube).phase_solutions(Vec::new(), ..sol_len_limit()) {
         let phase1_sol_len = phase1_sol.len();
 
         // check if the solution will be longer than the shortest solution
-        if phase1_sol.len() >= shortest_sol_length.load(Ordering::Relaxed) {
-            // Tell the threads to stop
-            search_flag.store(false, Ordering::Relaxed);
+        if phase1_sol.len() >= sol_len_limit() {
+            // only this thread stops, since parking the other threads could leave the iterator
+            // waiting for a solution which is never sent
             return;
         }
 
         let phase2_cube = phase1_cube.apply_moves(phase1_sol.iter().copied());
 
-        for phase2_sol in Phase2Node::from(phase2_cube)
-            .phase_solutions(phase1_sol, ..shortest_sol_length.load(Ordering::Relaxed))
+        for phase2_sol in
+            Phase2Node::from(phase2_cube).phase_solutions(phase1_sol, ..sol_len_limit())
         {
             // if we should not be searching then park the thread
             while !search_flag.load(Ordering::Relaxed) {
+                if stop_flag.load(Ordering::Relaxed) {
+                    return;
+                }
                 std::thread::park()
             }
 
             // check if the solution will be longer than the shortest solution
-            if phase2_sol.len() >= shortest_sol_length.load(Ordering::Relaxed) {
+            if phase2_sol.len() >= sol_len_limit() {
                 break;
             }
 
@@ -57,27 +87,31 @@ fn fast_solve(
 
             let phase3_node = Phase3Node::from(phase3_cube);
 
-            let shortest_sol = shortest_sol_length.load(Ordering::Relaxed);
-
             if let Some(solution) = phase3_node
-                .phase_solutions(phase2_sol, ..shortest_sol)
+                .phase_solutions(phase2_sol, ..sol_len_limit())
                 .next()
             {
-                // store the shorter solution length in the atomic
-                if shortest_sol_length.fetch_min(solution.len(), Ordering::AcqRel) > solution.len()
-                {
-                    // if the value was swapped then this is the shortest solution
+                let sol_len = solution.len();
+
+                // map the solution into twists
+                let twists = solution.into_iter().map(|m| *m.twist());
 
-                    let sol_len = solution.len();
+                // prepend the pre sequence
+                let twist_solution: TwistSequence =
+                    pre_sequence.iter().copied().chain(twists).collect();
 
-                    // map the solution into twists
-                    let twists = solution.into_iter().map(|m| *m.twist());
+                let cost = cost_model.map_or(0, |cost_model| cost_model.cost(&twist_solution));
+                let score = solution_score(sol_len, cost);
 
-                    // prepend the pre sequence
-                    let twist_solution = pre_sequence.iter().copied().chain(twists).collect();
+                // store the better score in the atomic
+                if best_score.fetch_min(score, Ordering::AcqRel) > score {
+                    // if the value was swapped then this is the best solution
+                    shortest_sol_length.fetch_min(sol_len, Ordering::AcqRel);
 
-                    // send the solution
-                    solutions.send((twist_solution, sol_len)).unwrap();
+                    // send the solution, stopping if the iterator was dropped
+                    if solutions.send((twist_solution, sol_len)).is_err() {
+                        return;
+                    }
                 }
             }
         }
@@ -86,60 +120,67 @@ fn fast_solve(
 
 /// An iterator over increasingly shorter solutions
 ///
-/// The iterator returns solutions and their corresponding lengths
+/// The iterator returns solutions and their corresponding lengths. If the iterator has a
+/// [`CostModel`], it also returns solutions with the same length as the last one which are
+/// cheaper.
 pub struct FastSolutionIterator {
     thread_handles: [JoinHandle<()>; 12],
     sol_receive: Receiver<(TwistSequence, usize)>,
     search_flag: Arc<AtomicBool>,
+    stop_flag: Arc<AtomicBool>,
+    cost_model: Option<CostModel>,
+    /// The score of the last solution returned
+    last_score: u64,
 }
 
 impl FastSolutionIterator {
     pub(crate) fn new(cube: Cube, max_sol_length: Option<usize>) -> Self {
+        Self::with_cost_model(cube, max_sol_length, None)
+    }
+
+    pub(crate) fn with_cost_model(
+        cube: Cube,
+        max_sol_length: Option<usize>,
+        cost_model: Option<CostModel>,
+    ) -> Self {
         let length = Arc::new(AtomicUsize::new(max_sol_length.unwrap_or(usize::MAX)));
+        // no solution with the maximum length has a lower score
+        let best_score = Arc::new(AtomicU64::new(
+            max_sol_length.map_or(u64::MAX, |max| solution_score(max, 0)),
+        ));
 
         let (sol_send, sol_receive) = sync_channel(0);
 
         // Define all orientations that we want to search in parallel from.
-        // This is all 12 orientations of the piece in the LDBO place
-        let orientations = [
-            Vec::new(),
-            vec![Twist::new(Face::R, TwistDirection::DBR, Layer::Both)],
-            vec![Twist::new(Face::R, TwistDirection::UFL, Layer::Both)],
-            vec![Twist::new(Face::U, TwistDirection::UBL, Layer::Both)],
-            vec![Twist::new(Face::U, TwistDirection::DFR, Layer::Both)],
-            vec![Twist::new(Face::F, TwistDirection::DFL, Layer::Both)],
-            vec![Twist::new(Face::F, TwistDirection::UBR, Layer::Both)],
-            vec![Twist::new(Face::I, TwistDirection::DBL, Layer::Both)],
-            vec![Twist::new(Face::I, TwistDirection::UFR, Layer::Both)],
-            vec![
-                Twist::new(Face::F, TwistDirection::DBR, Layer::Both),
-                Twist::new(Face::I, TwistDirection::DBR, Layer::Both),
-            ],
-            vec![
-                Twist::new(Face::F, TwistDirection::DFR, Layer::Both),
-                Twist::new(Face::I, TwistDirection::DFR, Layer::Both),
-            ],
-            vec![
-                Twist::new(Face::R, TwistDirection::UBR, Layer::Both),
-                Twist::new(Face::I, TwistDirection::UBR, Layer::Both),
-            ],
-        ]
-        .map(TwistSequence);
+        // This is all 12 orientations of the piece in the LDBO place, which are the rotations that
+        // keep the LDBO location in place
+        let orientations: [TwistSequence; 12] = Rotation::iter_rotations()
+            .filter(|rotation| rotation.rotate_location(PieceLocation::LAST) == PieceLocation::LAST)
+            .map(|rotation| rotation.to_twists())
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
 
         let search_flag = Arc::new(AtomicBool::new(false));
+        let stop_flag = Arc::new(AtomicBool::new(false));
 
         // spawn threads to search for solutions in parallel from different orientations
         let thread_handles = orientations.map(|twist_seq| {
             let c_length = length.clone();
+            let c_best_score = best_score.clone();
             let c_raw_sol_send = sol_send.clone();
             let c_search_flag = search_flag.clone();
+            let c_stop_flag = stop_flag.clone();
             let pre_seq = twist_seq.clone();
 
             std::thread::spawn(move || {
                 fast_solve(
                     CubieCube::from_cube(cube.twists(twist_seq)),
                     c_search_flag,
+                    c_stop_flag,
                     c_length,
+                    c_best_score,
+                    cost_model,
                     c_raw_sol_send,
                     pre_seq,
                 );
@@ -150,20 +191,58 @@ impl FastSolutionIterator {
             thread_handles,
             sol_receive,
             search_flag,
+            stop_flag,
+            cost_model,
+            last_score: u64::MAX,
         }
     }
-}
 
-impl Iterator for FastSolutionIterator {
-    type Item = (TwistSequence, usize);
-    fn next(&mut self) -> Option<Self::Item> {
+    /// Returns whether the solution is better than the last one returned, and if so makes it the
+    /// last one
+    ///
+    /// Threads which find better solutions at the same time can send them in either order, so the
+    /// worse one is skipped if it arrives second.
+    fn is_improvement(&mut self, (solution, sol_len): &(TwistSequence, usize)) -> bool {
+        let cost = self
+            .cost_model
+            .map_or(0, |cost_model| cost_model.cost(solution));
+        let score = solution_score(*sol_len, cost);
+
+        let is_improvement = score < self.last_score;
+        if is_improvement {
+            self.last_score = score;
+        }
+        is_improvement
+    }
+
+    /// Returns the next solution, or an error if the deadline passes before it is found
+    ///
+    /// After an error the search can be continued by calling this again.
+    pub(crate) fn next_before(
+        &mut self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<(TwistSequence, usize)>, TimedOut> {
+        loop {
+            match self.receive_before(deadline)? {
+                Some(solution) if !self.is_improvement(&solution) => continue,
+                solution => return Ok(solution),
+            }
+        }
+    }
+
+    /// Returns the next solution sent by the threads, or an error if the deadline passes before
+    /// one is sent
+    fn receive_before(
+        &mut self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<(TwistSequence, usize)>, TimedOut> {
         // If any of the threads have finished then all remaining bounds are in the receiver (if any)
         if self
             .thread_handles
             .iter()
             .any(|thread| thread.is_finished())
         {
-            return self.sol_receive.try_recv().ok();
+            return Ok(self.sol_receive.try_recv().ok());
         }
 
         // Otherwise tell the threads to start searching
@@ -175,12 +254,43 @@ impl Iterator for FastSolutionIterator {
         }
 
         // wait for a solution
-        let result = self.sol_receive.recv().ok();
+        let result = match deadline {
+            None => self
+                .sol_receive
+                .recv()
+                .map_err(|_| RecvTimeoutError::Disconnected),
+            Some(deadline) => self
+                .sol_receive
+                .recv_timeout(deadline.saturating_duration_since(Instant::now())),
+        };
 
         // tell the threads to stop searching
         self.search_flag.store(false, Ordering::Relaxed);
 
-        result
+        match result {
+            Ok(solution) => Ok(Some(solution)),
+            Err(RecvTimeoutError::Disconnected) => Ok(None),
+            Err(RecvTimeoutError::Timeout) => Err(TimedOut),
+        }
+    }
+}
+
+impl Drop for FastSolutionIterator {
+    fn drop(&mut self) {
+        // stop the threads once they are done with their current step so they don't stay parked
+        self.stop_flag.store(true, Ordering::Relaxed);
+
+        for thread in &self.thread_handles {
+            thread.thread().unpark()
+        }
+    }
+}
+
+impl Iterator for FastSolutionIterator {
+    type Item = (TwistSequence, usize);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_before(None)
+            .expect("a search without a deadline should not time out")
     }
 }
 
@@ -200,4 +310,20 @@ mod tests {
             assert!(cube.twists(sol.iter().copied()).is_solved());
         }
     }
+
+    #[test]
+    fn test_fast_solve_by_cost() {
+        let cube = Cube::SOLVED.twists(TwistSequence::from_str("IUFL UFLI BR FURI").unwrap());
+
+        let sol_iter = FastSolutionIterator::with_cost_model(cube, None, Some(CostModel::PHYSICAL));
+
+        let mut last_score = None;
+        for (sol, len) in sol_iter {
+            assert!(cube.twists(sol.iter().copied()).is_solved());
+
+            let score = (len, CostModel::PHYSICAL.cost(&sol));
+            assert!(last_score.is_none_or(|last_score| score < last_score));
+            last_score = Some(score);
+        }
+    }
 }
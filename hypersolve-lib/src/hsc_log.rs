@@ -0,0 +1,187 @@
+use std::fmt::Display;
+
+use crate::*;
+
+/// Version of the Hyperspeedcube log file format
+const HSC_LOG_VERSION: u32 = 1;
+
+/// Name of the puzzle type of the 2<sup>4</sup> in Hyperspeedcube
+const HSC_PUZZLE_TYPE: &str = "Rubiks4D";
+
+/// Number of layers of the 2<sup>4</sup>
+const HSC_LAYER_COUNT: u8 = 2;
+
+/// Errors for parsing Hyperspeedcube log files
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ParseHyperspeedcubeLogError {
+    #[error("invalid log file: {0}")]
+    InvalidFile(String),
+    #[error("unsupported log version `{0}`, expected version {HSC_LOG_VERSION}")]
+    UnsupportedVersion(u32),
+    #[error("log is for the `{0}` puzzle but only the 2^4 ({HSC_PUZZLE_TYPE} with {HSC_LAYER_COUNT} layers) is supported")]
+    WrongPuzzle(String),
+    #[error("invalid twist `{0}`: {1}")]
+    InvalidTwist(String, ParseTwistError),
+}
+
+/// The twists stored in a Hyperspeedcube log file of the 2<sup>4</sup>
+///
+/// Whole cube rotations in the log are twists of both layers.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct HyperspeedcubeLog {
+    /// Twists made by the scrambler
+    pub scramble: TwistSequence,
+    /// Twists made after scrambling
+    pub solution: TwistSequence,
+}
+
+/// The serialized form of a Hyperspeedcube log file
+#[derive(serde::Serialize, serde::Deserialize)]
+struct HyperspeedcubeLogFile {
+    version: u32,
+    puzzle: HyperspeedcubeLogPuzzle,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scramble_length: Option<usize>,
+    #[serde(default)]
+    scramble: String,
+    #[serde(default)]
+    twists: String,
+}
+
+/// The puzzle of a Hyperspeedcube log file
+#[derive(serde::Serialize, serde::Deserialize)]
+struct HyperspeedcubeLogPuzzle {
+    #[serde(rename = "type")]
+    puzzle_type: String,
+    layer_count: u8,
+}
+
+impl HyperspeedcubeLog {
+    /// Creates a new log from the scramble and the twists made after it
+    pub fn new(scramble: TwistSequence, solution: TwistSequence) -> Self {
+        HyperspeedcubeLog { scramble, solution }
+    }
+}
+
+/// Parses twists in Hyperspeedcube notation
+fn parse_hyperspeedcube_twists(s: &str) -> Result<TwistSequence, ParseHyperspeedcubeLogError> {
+    s.split_whitespace()
+        .map(|twist| {
+            Notation::Hyperspeedcube
+                .parse_twist(twist)
+                .map_err(|err| ParseHyperspeedcubeLogError::InvalidTwist(twist.to_string(), err))
+        })
+        .collect()
+}
+
+impl std::str::FromStr for HyperspeedcubeLog {
+    type Err = ParseHyperspeedcubeLogError;
+
+    /// Parses a Hyperspeedcube log file
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let file: HyperspeedcubeLogFile = serde_yaml::from_str(s)
+            .map_err(|err| ParseHyperspeedcubeLogError::InvalidFile(err.to_string()))?;
+
+        if file.version != HSC_LOG_VERSION {
+            return Err(ParseHyperspeedcubeLogError::UnsupportedVersion(
+                file.version,
+            ));
+        }
+
+        if file.puzzle.puzzle_type != HSC_PUZZLE_TYPE || file.puzzle.layer_count != HSC_LAYER_COUNT
+        {
+            return Err(ParseHyperspeedcubeLogError::WrongPuzzle(format!(
+                "{} with {} layers",
+                file.puzzle.puzzle_type, file.puzzle.layer_count
+            )));
+        }
+
+        Ok(HyperspeedcubeLog {
+            scramble: parse_hyperspeedcube_twists(&file.scramble)?,
+            solution: parse_hyperspeedcube_twists(&file.twists)?,
+        })
+    }
+}
+
+impl Display for HyperspeedcubeLog {
+    /// Formats the log as a Hyperspeedcube log file
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let file = HyperspeedcubeLogFile {
+            version: HSC_LOG_VERSION,
+            puzzle: HyperspeedcubeLogPuzzle {
+                puzzle_type: HSC_PUZZLE_TYPE.to_string(),
+                layer_count: HSC_LAYER_COUNT,
+            },
+            scramble_length: (!self.scramble.is_empty()).then_some(self.scramble.len()),
+            scramble: self.scramble.to_notation(Notation::Hyperspeedcube),
+            twists: self.solution.to_notation(Notation::Hyperspeedcube),
+        };
+
+        let yaml = serde_yaml::to_string(&file).map_err(|_| std::fmt::Error)?;
+        write!(f, "{}", yaml)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hyperspeedcube_log_round_trip() {
+        let scramble = "RO IUF {2}BDR IF2".parse::<TwistSequence>().unwrap();
+        let solution = Notation::Hyperspeedcube
+            .parse_twist_sequence("RUF @IU {2}OR2 @RDBL")
+            .unwrap();
+
+        for log in [
+            HyperspeedcubeLog::new(scramble.clone(), solution.clone()),
+            HyperspeedcubeLog::new(scramble, TwistSequence::default()),
+            HyperspeedcubeLog::new(TwistSequence::default(), solution),
+            HyperspeedcubeLog::default(),
+        ] {
+            assert_eq!(log.to_string().parse(), Ok(log));
+        }
+    }
+
+    #[test]
+    fn test_parse_hyperspeedcube_log() {
+        let log = "version: 1
+puzzle:
+  type: Rubiks4D
+  layer_count: 2
+scramble_length: 2
+scramble: RUF IR2
+twists: >-
+  @OU {2}IDB
+  FUR
+";
+
+        let expected = HyperspeedcubeLog::new(
+            Notation::Hyperspeedcube
+                .parse_twist_sequence("RUF IR2")
+                .unwrap(),
+            Notation::Hyperspeedcube
+                .parse_twist_sequence("@OU {2}IDB FUR")
+                .unwrap(),
+        );
+        assert!(expected.solution[0].is_cube_rotation());
+        assert_eq!(log.parse(), Ok(expected));
+
+        assert_eq!(
+            log.replace("layer_count: 2", "layer_count: 3")
+                .parse::<HyperspeedcubeLog>(),
+            Err(ParseHyperspeedcubeLogError::WrongPuzzle(
+                "Rubiks4D with 3 layers".to_string()
+            ))
+        );
+        assert_eq!(
+            log.replace("version: 1", "version: 2")
+                .parse::<HyperspeedcubeLog>(),
+            Err(ParseHyperspeedcubeLogError::UnsupportedVersion(2))
+        );
+        assert!(matches!(
+            log.replace("FUR", "FXR").parse::<HyperspeedcubeLog>(),
+            Err(ParseHyperspeedcubeLogError::InvalidTwist(..))
+        ));
+    }
+}
@@ -1,15 +1,46 @@
+mod alg_search;
 mod bound;
+mod color_scheme;
+mod cost_model;
 mod cube_index;
 mod data_loading;
 mod depth_queue;
 mod fast_solve;
+#[cfg(feature = "serde")]
+mod hsc_log;
 mod node;
+mod phase_stats;
 mod prune;
 mod pub_api;
+#[cfg(feature = "render")]
+mod render;
+mod scramble_filter;
+mod scramble_key;
 mod simple_solve;
 mod solution_iterators;
+mod study;
+mod subset;
+mod verify;
 
 pub(crate) use node::*;
 pub(crate) use prune::*;
 
+pub use alg_search::*;
+pub use color_scheme::*;
+pub use cost_model::*;
+#[cfg(feature = "serde")]
+pub use hsc_log::*;
+pub use phase_stats::*;
 pub use pub_api::*;
+#[cfg(feature = "render")]
+pub use render::*;
+pub use scramble_filter::*;
+pub use scramble_key::*;
+pub use study::*;
+pub use subset::*;
+pub use verify::*;
+
+#[cfg(test)]
+fn twists(s: &str) -> hypersolve_core::TwistSequence {
+    s.parse().unwrap()
+}
@@ -0,0 +1,713 @@
+use std::alloc::Layout;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use hypersolve_core::{
+    Axis, Face, Move, Phase, Phase2, PhaseEnum, PieceLocation, Rotation, N_C3_COORD_STATES,
+    N_IO_COORD_STATES,
+};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::{CubeIndex, Node, Phase1Node, Phase2Node, Phase3Node};
+
+/// The maximum number of states at the largest distance kept by [`PhaseStats`]
+pub const N_ANTIPODE_EXAMPLES: usize = 10;
+
+/// The number of states of a phase at each distance from the goal of the phase
+///
+/// The distances are found by a breadth first search over every node of the phase using the moves
+/// of the phase, so the largest distance is the exact number of moves the phase needs in the worst
+/// case. Phase 2 has too many nodes to store each of them, so it is searched up to the mirror
+/// symmetry which swaps the `X` and `Y` axes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhaseStats {
+    phase: PhaseEnum,
+    depth_counts: Vec<u64>,
+    antipodes: Vec<CubeIndex>,
+}
+
+impl PhaseStats {
+    /// Searches every node of the phase, calling `on_depth` with each distance and the number of
+    /// states at it as soon as they are found
+    ///
+    /// The search uses every thread of the global rayon pool. Phase 3 is searched in seconds,
+    /// phase 1 needs about 300 MB of memory and minutes, and phase 2 needs about 5 GB of memory
+    /// and hours.
+    pub fn generate(phase: PhaseEnum, on_depth: impl FnMut(usize, u64)) -> Self {
+        let (depth_counts, antipodes) = match phase {
+            PhaseEnum::Phase1 => search(&NodeSpace::<Phase1Node>::new(), None, on_depth),
+            PhaseEnum::Phase2 => search(&Phase2Space::new(), None, on_depth),
+            PhaseEnum::Phase3 => search(&NodeSpace::<Phase3Node>::new(), None, on_depth),
+        };
+
+        PhaseStats {
+            phase,
+            depth_counts,
+            antipodes,
+        }
+    }
+
+    /// Returns the phase
+    pub fn phase(&self) -> PhaseEnum {
+        self.phase
+    }
+
+    /// Returns the number of states at each distance, starting with the goal at distance 0
+    pub fn depth_counts(&self) -> &[u64] {
+        &self.depth_counts
+    }
+
+    /// Returns the number of states which were found
+    pub fn n_states(&self) -> u64 {
+        self.depth_counts.iter().sum()
+    }
+
+    /// Returns the number of states of the phase
+    pub fn expected_n_states(&self) -> u64 {
+        match self.phase {
+            PhaseEnum::Phase1 => Phase1Node::N_STATES as u64,
+            PhaseEnum::Phase2 => Phase2Node::N_STATES as u64,
+            PhaseEnum::Phase3 => Phase3Node::N_STATES as u64,
+        }
+    }
+
+    /// Returns the largest distance of any state from the goal
+    pub fn max_depth(&self) -> usize {
+        self.depth_counts.len() - 1
+    }
+
+    /// Returns the largest distance the solver assumes for the phase
+    pub fn expected_max_depth(&self) -> usize {
+        self.phase.max_depth()
+    }
+
+    /// Returns the number of states at the largest distance
+    pub fn n_antipodes(&self) -> u64 {
+        self.depth_counts[self.max_depth()]
+    }
+
+    /// Returns up to [`N_ANTIPODE_EXAMPLES`] states at the largest distance
+    ///
+    /// Each state is given by the cube index of the state with every coordinate of the other
+    /// phases solved.
+    pub fn antipodes(&self) -> &[CubeIndex] {
+        &self.antipodes
+    }
+
+    /// Returns whether every state was found and the largest distance is the one the solver
+    /// assumes
+    pub fn is_consistent(&self) -> bool {
+        self.n_states() == self.expected_n_states() && self.max_depth() == self.expected_max_depth()
+    }
+}
+
+/// Returns the cube index of the state with the given node index in the phase and every other
+/// coordinate solved
+fn phase_cube_index(phase: PhaseEnum, node_index: u64) -> CubeIndex {
+    let stride = match phase {
+        PhaseEnum::Phase1 => Phase2Node::N_STATES as u128 * Phase3Node::N_STATES as u128,
+        PhaseEnum::Phase2 => Phase3Node::N_STATES as u128,
+        PhaseEnum::Phase3 => 1,
+    };
+
+    CubeIndex(node_index as u128 * stride)
+}
+
+/// A graph of nodes searched by [`search()`], where each node may stand for a class of symmetric
+/// states
+trait SearchSpace: Sync {
+    /// The number of indices, some of which may not stand for a class
+    fn len(&self) -> u64;
+
+    /// The index of the goal
+    fn goal(&self) -> u64;
+
+    /// Returns the indices which don't stand for a class
+    fn unused(&self) -> impl Iterator<Item = u64> + '_ {
+        std::iter::empty()
+    }
+
+    /// Returns whether the index stands for a class
+    fn is_used(&self, _index: u64) -> bool {
+        true
+    }
+
+    /// Returns the number of states in the class
+    fn class_size(&self, _index: u64) -> u64 {
+        1
+    }
+
+    /// Returns the indices of the classes one move away from the class
+    fn neighbors(&self, index: u64) -> impl Iterator<Item = u64> + '_;
+
+    /// Returns the cube index of a state in the class with every other coordinate solved
+    fn cube_index(&self, index: u64) -> CubeIndex;
+}
+
+/// The number of classes, the number of states and up to [`N_ANTIPODE_EXAMPLES`] classes found at
+/// a depth
+#[derive(Default)]
+struct DepthResult(u64, u64, Vec<u64>);
+
+impl DepthResult {
+    fn add(&mut self, space: &impl SearchSpace, index: u64) {
+        self.0 += 1;
+        self.1 += space.class_size(index);
+        if self.2.len() < N_ANTIPODE_EXAMPLES {
+            self.2.push(index);
+        }
+    }
+
+    fn merge(mut self, mut other: Self) -> Self {
+        self.0 += other.0;
+        self.1 += other.1;
+        self.2.append(&mut other.2);
+        self.2.truncate(N_ANTIPODE_EXAMPLES);
+        self
+    }
+}
+
+/// Searches the space breadth first from the goal up to the maximum depth, returning the number of
+/// states at each depth and examples of the states at the largest depth
+///
+/// Each depth is found either forwards by visiting the neighbors of the last depth or, once there
+/// are more classes in the last depth than unvisited classes, backwards by checking which
+/// unvisited classes have a neighbor in the last depth.
+fn search<S: SearchSpace>(
+    space: &S,
+    max_depth: Option<usize>,
+    mut on_depth: impl FnMut(usize, u64),
+) -> (Vec<u64>, Vec<CubeIndex>) {
+    let table = DepthTable::new(space.len());
+
+    let mut n_unvisited = space.len() - 1;
+    for index in space.unused() {
+        table.visit(index, 1);
+        n_unvisited -= 1;
+    }
+
+    table.visit(space.goal(), DepthTable::mark(0));
+    let mut last = DepthResult(1, space.class_size(space.goal()), vec![space.goal()]);
+    let mut depth_counts = vec![last.1];
+    on_depth(0, last.1);
+
+    while max_depth.is_none_or(|max_depth| depth_counts.len() <= max_depth) {
+        let depth = depth_counts.len();
+        let last_mark = DepthTable::mark(depth - 1);
+        let mark = DepthTable::mark(depth);
+
+        let result = if last.0 > n_unvisited {
+            (0..table.n_words())
+                .into_par_iter()
+                .map(|word| {
+                    let mut result = DepthResult::default();
+
+                    for index in table.with_entry(word, 0) {
+                        // a neighbor of an unvisited class is at least at the last depth, so
+                        // the neighbors with its mark are at the last depth
+                        if space
+                            .neighbors(index)
+                            .any(|index| table.get(index) == last_mark)
+                        {
+                            table.visit(index, mark);
+                            result.add(space, index);
+                        }
+                    }
+
+                    result
+                })
+                .reduce(DepthResult::default, DepthResult::merge)
+        } else {
+            (0..table.n_words())
+                .into_par_iter()
+                .map(|word| {
+                    let mut result = DepthResult::default();
+
+                    // this also visits the neighbors of classes 3 depths earlier, which are all
+                    // visited already
+                    for index in table
+                        .with_entry(word, last_mark)
+                        .filter(|&index| space.is_used(index))
+                    {
+                        for index in space.neighbors(index) {
+                            if table.visit(index, mark) {
+                                result.add(space, index);
+                            }
+                        }
+                    }
+
+                    result
+                })
+                .reduce(DepthResult::default, DepthResult::merge)
+        };
+
+        if result.0 == 0 {
+            break;
+        }
+
+        on_depth(depth, result.1);
+        depth_counts.push(result.1);
+
+        n_unvisited -= result.0;
+        last = result;
+    }
+
+    let mut antipodes = last.2;
+    antipodes.sort();
+    let antipodes = antipodes
+        .into_iter()
+        .map(|index| space.cube_index(index))
+        .collect();
+
+    (depth_counts, antipodes)
+}
+
+/// The depth of each index modulo 3 in 2 bits per index, which can be set from multiple threads
+///
+/// Each entry is 0 for an unvisited index and otherwise its mark, 1 more than the depth modulo 3.
+/// The classes at a depth are only found from the classes at the depth before it, which can't be
+/// confused with the classes 3 depths earlier when searching in either direction.
+struct DepthTable {
+    words: Box<[AtomicU64]>,
+    len: u64,
+}
+
+impl DepthTable {
+    /// The number of entries in each word
+    const ENTRIES_PER_WORD: u64 = 32;
+
+    /// Creates a table of unvisited indices less than `len`
+    fn new(len: u64) -> Self {
+        let n_words = len.div_ceil(Self::ENTRIES_PER_WORD).max(1) as usize;
+        let layout = Layout::array::<AtomicU64>(n_words).expect("the table should fit in memory");
+
+        // allocating zeroed memory lets the system map the pages lazily, so a table only uses
+        // memory for the parts which have been visited
+        let words = unsafe {
+            let ptr = std::alloc::alloc_zeroed(layout) as *mut AtomicU64;
+            if ptr.is_null() {
+                std::alloc::handle_alloc_error(layout);
+            }
+
+            // SAFTEY: zeroed memory is a valid atomic integer and the memory was allocated with the
+            // layout of the slice
+            Box::from_raw(std::ptr::slice_from_raw_parts_mut(ptr, n_words))
+        };
+
+        DepthTable { words, len }
+    }
+
+    /// Returns the mark of the depth
+    const fn mark(depth: usize) -> u8 {
+        (depth % 3) as u8 + 1
+    }
+
+    /// Returns the number of words
+    fn n_words(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Returns the entry of the index
+    fn get(&self, index: u64) -> u8 {
+        let word = self.words[(index / Self::ENTRIES_PER_WORD) as usize].load(Ordering::Relaxed);
+        (word >> (2 * (index % Self::ENTRIES_PER_WORD)) & 3) as u8
+    }
+
+    /// Sets the entry of the index to the mark if it is unvisited, returning whether it was
+    ///
+    /// Every thread setting entries at the same time has to use the same mark.
+    fn visit(&self, index: u64, mark: u8) -> bool {
+        let shift = 2 * (index % Self::ENTRIES_PER_WORD);
+        let word = &self.words[(index / Self::ENTRIES_PER_WORD) as usize];
+
+        word.load(Ordering::Relaxed) >> shift & 3 == 0
+            && word.fetch_or((mark as u64) << shift, Ordering::Relaxed) >> shift & 3 == 0
+    }
+
+    /// Returns the indices of the word which are less than the length and have the entry
+    fn with_entry(&self, word: usize, entry: u8) -> impl Iterator<Item = u64> {
+        const LOW_BITS: u64 = 0x5555_5555_5555_5555;
+
+        // the entries which differ from the given entry have a set bit in the difference
+        let difference = self.words[word].load(Ordering::Relaxed) ^ (entry as u64 * LOW_BITS);
+        let mut matches = !(difference | difference >> 1) & LOW_BITS;
+
+        let start = word as u64 * Self::ENTRIES_PER_WORD;
+        let len = self.len;
+
+        std::iter::from_fn(move || {
+            if matches == 0 {
+                return None;
+            }
+
+            let bit = matches.trailing_zeros() as u64;
+            matches &= matches - 1;
+            Some(start + bit / 2)
+        })
+        .take_while(move |&index| index < len)
+    }
+}
+
+/// The nodes of a phase without symmetry
+struct NodeSpace<N>(PhantomData<N>);
+
+impl<N: Node> NodeSpace<N> {
+    fn new() -> Self {
+        NodeSpace(PhantomData)
+    }
+}
+
+impl<N: Node + Sync> SearchSpace for NodeSpace<N>
+where
+    N::Index: TryFrom<u64>,
+    <N::Index as TryFrom<u64>>::Error: std::fmt::Debug,
+{
+    fn len(&self) -> u64 {
+        N::N_STATES as u64
+    }
+
+    fn goal(&self) -> u64 {
+        N::GOAL.index().into()
+    }
+
+    fn neighbors(&self, index: u64) -> impl Iterator<Item = u64> + '_ {
+        let node = N::from_index(index.try_into().unwrap());
+
+        Move::<N::Phase>::iter().map(move |m| node.apply_move(m).index().into())
+    }
+
+    fn cube_index(&self, index: u64) -> CubeIndex {
+        phase_cube_index(N::Phase::PHASE_ENUM, index)
+    }
+}
+
+/// The number of symmetries phase 2 is searched up to
+const N_PHASE2_SYMMETRIES: usize = 2;
+
+/// The nodes of phase 2 up to the mirror symmetry which swaps the `X` and `Y` axes
+///
+/// This is the only symmetry of the cube which fixes the LDBO piece and the `I` and `O` cells and
+/// is compatible with the C3 coordinate, since the orientation of a piece is defined by whether it
+/// swaps the stickers on the `X` and `Y` axes. Each class is indexed by its representative IO
+/// coordinate and the smallest C3 coordinate of the states in the class with that IO coordinate.
+struct Phase2Space {
+    /// The C3 coordinate each symmetry moves each C3 coordinate to
+    c3_symmetries: Vec<Vec<u32>>,
+    /// The IO coordinate which represents each class of IO coordinates
+    io_representatives: Vec<u16>,
+    /// The class of each IO coordinate and a symmetry which moves it to the representative
+    io_classes: Vec<(u16, u8)>,
+    /// The symmetries which fix each representative IO coordinate
+    stabilizers: Vec<Vec<u8>>,
+}
+
+impl Phase2Space {
+    fn new() -> Self {
+        let move_symmetries = phase2_move_symmetries();
+        assert_eq!(move_symmetries.len(), N_PHASE2_SYMMETRIES);
+
+        let io_symmetries = move_symmetries
+            .iter()
+            .map(|moves| {
+                coordinate_symmetry(N_IO_COORD_STATES as usize, moves, |io_coord, m| {
+                    let node = Phase2Node {
+                        c3_coord: 0,
+                        io_coord: io_coord as u16,
+                    };
+                    node.apply_move(m).io_coord as u32
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let c3_symmetries = move_symmetries
+            .iter()
+            .map(|moves| {
+                coordinate_symmetry(N_C3_COORD_STATES as usize, moves, |c3_coord, m| {
+                    let node = Phase2Node {
+                        c3_coord,
+                        io_coord: 0,
+                    };
+                    node.apply_move(m).c3_coord
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut io_representatives = Vec::new();
+        let mut io_classes = vec![(0, 0); N_IO_COORD_STATES as usize];
+        let mut stabilizers = Vec::new();
+
+        for io_coord in 0..N_IO_COORD_STATES as usize {
+            let images = io_symmetries.iter().map(|symmetry| symmetry[io_coord]);
+
+            // the representative is the smallest coordinate of the class
+            let (representative, symmetry) = images
+                .clone()
+                .zip(0..)
+                .min()
+                .expect("there should be at least one symmetry");
+
+            if representative as usize == io_coord {
+                stabilizers.push(
+                    images
+                        .zip(0..)
+                        .filter(|&(image, _)| image as usize == io_coord)
+                        .map(|(_, symmetry)| symmetry)
+                        .collect(),
+                );
+                io_representatives.push(io_coord as u16);
+            }
+
+            let class = io_representatives
+                .iter()
+                .position(|&coord| coord as u32 == representative)
+                .expect("the representative should be the first coordinate of its class");
+            io_classes[io_coord] = (class as u16, symmetry);
+        }
+
+        Phase2Space {
+            c3_symmetries,
+            io_representatives,
+            io_classes,
+            stabilizers,
+        }
+    }
+
+    /// Returns the symmetries which fix the representative of the class and the C3 coordinate
+    fn c3_stabilizer(&self, class: usize, c3_coord: u32) -> impl Iterator<Item = u8> + '_ {
+        self.stabilizers[class]
+            .iter()
+            .copied()
+            .filter(move |&symmetry| {
+                self.c3_symmetries[symmetry as usize][c3_coord as usize] == c3_coord
+            })
+    }
+
+    /// Returns whether the C3 coordinate is the smallest of the states in the class with the
+    /// representative IO coordinate
+    fn is_representative(&self, class: usize, c3_coord: u32) -> bool {
+        self.stabilizers[class]
+            .iter()
+            .all(|&symmetry| self.c3_symmetries[symmetry as usize][c3_coord as usize] >= c3_coord)
+    }
+
+    /// Returns the index of the class of the node
+    fn class_index(&self, node: Phase2Node) -> u64 {
+        let (class, symmetry) = self.io_classes[node.io_coord as usize];
+        let c3_coord = self.c3_symmetries[symmetry as usize][node.c3_coord as usize];
+
+        let c3_coord = self.stabilizers[class as usize]
+            .iter()
+            .map(|&symmetry| self.c3_symmetries[symmetry as usize][c3_coord as usize])
+            .min()
+            .expect("the identity should fix every coordinate");
+
+        class as u64 * N_C3_COORD_STATES as u64 + c3_coord as u64
+    }
+
+    /// Returns the node which represents the class
+    fn representative(&self, index: u64) -> Phase2Node {
+        Phase2Node {
+            c3_coord: (index % N_C3_COORD_STATES as u64) as u32,
+            io_coord: self.io_representatives[(index / N_C3_COORD_STATES as u64) as usize],
+        }
+    }
+}
+
+impl SearchSpace for Phase2Space {
+    fn len(&self) -> u64 {
+        self.io_representatives.len() as u64 * N_C3_COORD_STATES as u64
+    }
+
+    fn goal(&self) -> u64 {
+        self.class_index(Phase2Node::GOAL)
+    }
+
+    fn unused(&self) -> impl Iterator<Item = u64> + '_ {
+        (0..self.io_representatives.len())
+            .filter(|&class| self.stabilizers[class].len() > 1)
+            .flat_map(move |class| {
+                (0..N_C3_COORD_STATES)
+                    .filter(move |&c3_coord| !self.is_representative(class, c3_coord))
+                    .map(move |c3_coord| class as u64 * N_C3_COORD_STATES as u64 + c3_coord as u64)
+            })
+    }
+
+    fn is_used(&self, index: u64) -> bool {
+        let class = (index / N_C3_COORD_STATES as u64) as usize;
+        let c3_coord = (index % N_C3_COORD_STATES as u64) as u32;
+
+        self.is_representative(class, c3_coord)
+    }
+
+    fn class_size(&self, index: u64) -> u64 {
+        let class = (index / N_C3_COORD_STATES as u64) as usize;
+        let c3_coord = (index % N_C3_COORD_STATES as u64) as u32;
+
+        (N_PHASE2_SYMMETRIES / self.c3_stabilizer(class, c3_coord).count()) as u64
+    }
+
+    fn neighbors(&self, index: u64) -> impl Iterator<Item = u64> + '_ {
+        let node = self.representative(index);
+
+        Move::<Phase2>::iter().map(move |m| self.class_index(node.apply_move(m)))
+    }
+
+    fn cube_index(&self, index: u64) -> CubeIndex {
+        phase_cube_index(PhaseEnum::Phase2, self.representative(index).index())
+    }
+}
+
+/// Returns the phase 2 move each phase 2 move is moved to by each symmetry of the cube which fixes
+/// the LDBO piece and the `Z` and `W` axes, starting with the identity
+///
+/// Each symmetry is a rotation, optionally after mirroring the cube across the `X` axis.
+fn phase2_move_symmetries() -> Vec<Vec<Move<Phase2>>> {
+    let moves = Move::<Phase2>::iter().collect::<Vec<_>>();
+
+    [false, true]
+        .into_iter()
+        .flat_map(|mirror| Rotation::iter_rotations().map(move |rotation| (mirror, rotation)))
+        .filter(|&(mirror, rotation)| {
+            let last = match mirror {
+                true => PieceLocation::LAST.mirror(Axis::X),
+                false => PieceLocation::LAST,
+            };
+
+            rotation.rotate_location(last) == PieceLocation::LAST
+                && rotation.rotate_face(Face::F).axis() == Axis::Z
+                && rotation.rotate_face(Face::O).axis() == Axis::W
+        })
+        .map(|(mirror, rotation)| {
+            moves
+                .iter()
+                .map(|m| {
+                    let twist = match mirror {
+                        true => m.twist().mirror(Axis::X),
+                        false => *m.twist(),
+                    };
+                    let cube = hypersolve_core::Cube::SOLVED.twist(twist.transform_by(rotation));
+
+                    *moves
+                        .iter()
+                        .find(|m| hypersolve_core::Cube::SOLVED.twist(*m.twist()) == cube)
+                        .expect("a symmetry should move each phase 2 move to a phase 2 move")
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns the coordinate each coordinate is moved to by a symmetry, given the move each move is
+/// moved to by the symmetry
+///
+/// The symmetry fixes the solved coordinate and moves the coordinate after a move to the
+/// coordinate after the symmetric move, so the table is found by a search from the solved
+/// coordinate. This also checks that the coordinate is compatible with the symmetry.
+fn coordinate_symmetry(
+    n_states: usize,
+    moves: &[Move<Phase2>],
+    apply_move: impl Fn(u32, Move<Phase2>) -> u32,
+) -> Vec<u32> {
+    let mut table = vec![u32::MAX; n_states];
+    let mut stack = vec![0];
+    table[0] = 0;
+
+    while let Some(coord) = stack.pop() {
+        for m in Move::<Phase2>::iter() {
+            let next = apply_move(coord, m);
+            let image = apply_move(table[coord as usize], moves[m.into_usize()]);
+
+            if table[next as usize] == u32::MAX {
+                table[next as usize] = image;
+                stack.push(next);
+            } else {
+                assert_eq!(
+                    table[next as usize], image,
+                    "the coordinate should be compatible with the symmetry"
+                );
+            }
+        }
+    }
+
+    assert!(
+        !table.contains(&u32::MAX),
+        "every coordinate should be reachable"
+    );
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    /// Returns the number of nodes at each depth up to the maximum depth and the indices of the
+    /// nodes at the maximum depth by searching without symmetry
+    fn depth_counts<N: Node>(max_depth: usize) -> (Vec<u64>, HashSet<u64>) {
+        let mut visited = HashSet::from([N::GOAL.index().into()]);
+        let mut layer = vec![N::GOAL];
+        let mut counts = vec![1];
+
+        for _ in 0..max_depth {
+            layer = layer
+                .iter()
+                .flat_map(|node| Move::<N::Phase>::iter().map(|m| node.apply_move(m)))
+                .filter(|node| visited.insert(node.index().into()))
+                .collect();
+            counts.push(layer.len() as u64);
+        }
+
+        (
+            counts,
+            layer.iter().map(|node| node.index().into()).collect(),
+        )
+    }
+
+    #[test]
+    fn test_node_space_search() {
+        let (counts, antipodes) = search(&NodeSpace::<Phase1Node>::new(), Some(2), |_, _| ());
+        let (expected_counts, layer) = depth_counts::<Phase1Node>(2);
+        assert_eq!(counts, expected_counts);
+        assert_eq!(antipodes.len(), N_ANTIPODE_EXAMPLES);
+
+        for cube_index in antipodes {
+            let node = Phase1Node::from(hypersolve_core::CubieCube::from(cube_index));
+            assert!(layer.contains(&(node.index() as u64)));
+        }
+
+        let (counts, _) = search(&NodeSpace::<Phase3Node>::new(), Some(3), |_, _| ());
+        assert_eq!(counts, depth_counts::<Phase3Node>(3).0);
+    }
+
+    #[test]
+    fn test_phase2_symmetry_search() {
+        let space = Phase2Space::new();
+
+        let (counts, antipodes) = search(&space, Some(3), |_, _| ());
+        let (expected_counts, layer) = depth_counts::<Phase2Node>(3);
+        assert_eq!(counts, expected_counts);
+        assert!(!antipodes.is_empty());
+
+        for cube_index in antipodes {
+            let node = Phase2Node::from(hypersolve_core::CubieCube::from(cube_index));
+            assert!(layer.contains(&node.index()));
+        }
+    }
+
+    #[test]
+    fn test_phase_stats() {
+        let stats = PhaseStats {
+            phase: PhaseEnum::Phase3,
+            depth_counts: vec![1, 12, 40],
+            antipodes: Vec::new(),
+        };
+
+        assert_eq!(stats.n_states(), 53);
+        assert_eq!(stats.max_depth(), 2);
+        assert_eq!(stats.n_antipodes(), 40);
+        assert_eq!(stats.expected_max_depth(), 21);
+        assert!(!stats.is_consistent());
+    }
+}
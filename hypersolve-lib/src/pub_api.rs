@@ -3,13 +3,20 @@ use std::ops::RangeBounds;
 use hypersolve_core::{CubieCube, Phase, Phase1, Phase2, Phase3};
 
 use crate::simple_solve::simple_solve;
-use crate::{Node, Phase1Node, Phase2Node, Phase3Node};
+use crate::{AlgSearchIterator, CostModel, GoalMask, Node, Phase1Node, Phase2Node, Phase3Node};
 
 pub use crate::cube_index::{CubeIndex, CubeIndexError};
 pub use crate::fast_solve::FastSolutionIterator;
-pub use crate::solution_iterators::{FixedLengthSolutionIterator, ShortestSolutionIterator};
+pub use crate::solution_iterators::{
+    CheckpointError, FixedLengthSolutionIterator, ParallelSolutionIterator, PhaseCheckpoint,
+    RelativeSolutionIterator, SearchCheckpoint, ShortestSolutionIterator, TimedOut,
+};
 pub use hypersolve_core::{
-    Notation, ParseMC4DTwistError, ParseStandardTwistError, ParseTwistError, Twist, TwistSequence,
+    Algorithm, AlgorithmNode, Axis, Face, FaceletError, Layer, MC4DLog, Notation,
+    ParseAlgorithmError, ParseHyperspeedcubeTwistError, ParseMC4DLogError, ParseMC4DTwistError,
+    ParsePieceLocationError, ParseStandardTwistError, ParseTwistError, PhaseEnum, PieceCycle,
+    PieceLocation, Rotation, RotationMode, SequenceEquivalence, SpannedParseError, Twist,
+    TwistDirection, TwistSequence, A4, N_FACELETS,
 };
 
 pub use crate::bound::{Bound, BoundIterator};
@@ -25,9 +32,15 @@ pub const GODS_NUMBER_UPPER_BOUND: usize =
     Phase1::MAX_DEPTH + Phase2::MAX_DEPTH + Phase3::MAX_DEPTH;
 
 /// A 2<sup>4</sup> Rubik's Cube
-#[derive(Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct Cube(pub(crate) hypersolve_core::Cube);
 
+impl std::fmt::Display for Cube {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 impl Cube {
     /// The solved cube
     pub const SOLVED: Self = Cube(hypersolve_core::Cube::SOLVED);
@@ -48,6 +61,56 @@ impl Cube {
         cube
     }
 
+    /// Returns the face each sticker belongs to, ordered by the cell it is on
+    ///
+    /// Cells are ordered `R`, `L`, `U`, `D`, `F`, `B`, `O`, `I` and each contains 8 stickers.
+    pub fn facelets(&self) -> [Face; N_FACELETS] {
+        self.0.facelets()
+    }
+
+    /// Returns the solved location of the piece each sticker belongs to, ordered like
+    /// [`facelets()`](#method.facelets)
+    pub fn facelet_pieces(&self) -> [PieceLocation; N_FACELETS] {
+        self.0.facelet_pieces()
+    }
+
+    /// Creates a cube from the face each sticker belongs to
+    pub fn from_facelets(facelets: &[Face; N_FACELETS]) -> Result<Self, FaceletError> {
+        hypersolve_core::Cube::from_facelets(facelets).map(Cube)
+    }
+
+    /// Returns the facelets as a string of face symbols with the cells separated by spaces
+    pub fn to_facelet_string(&self) -> String {
+        self.0.to_facelet_string()
+    }
+
+    /// Creates a cube from a string of face symbols, ignoring whitespace
+    pub fn from_facelet_string(s: &str) -> Result<Self, FaceletError> {
+        hypersolve_core::Cube::from_facelet_string(s).map(Cube)
+    }
+
+    /// Returns whether two cubes are in the same state, optionally ignoring the orientation of the
+    /// whole cube
+    pub fn is_equivalent(&self, other: &Cube, up_to_rotation: bool) -> bool {
+        self.0.is_equivalent(&other.0, up_to_rotation)
+    }
+
+    /// Returns the mirror image of the cube across the given axis
+    pub fn mirror(&self, axis: Axis) -> Self {
+        Self(self.0.mirror(axis))
+    }
+
+    /// Returns the rotation which turns this cube into the other, or `None` if they are not the
+    /// same up to rotation
+    pub fn rotation_to(&self, other: &Cube) -> Option<Rotation> {
+        self.0.rotation_to(&other.0)
+    }
+
+    /// Returns the cycles of pieces which are not solved, with the rotation of the cube removed
+    pub fn cycles(&self) -> Vec<PieceCycle> {
+        self.0.cycles()
+    }
+
     /// Deterministically finds a solution to the cube as quickly as possible
     ///
     /// The solution length is garanteed to be less than or equal to [`GODS_NUMBER_UPPER_BOUND`]
@@ -58,16 +121,128 @@ impl Cube {
             .collect()
     }
 
+    /// Returns lower bounds on the number of moves needed to solve the part of the cube that each
+    /// of the 3 phases solves, as given by the pruning table of each phase
+    ///
+    /// The phase 1 bound is also a lower bound on the length of any solution.
+    pub fn phase_depths(&self) -> [usize; 3] {
+        let cube = CubieCube::from_cube(self.0);
+
+        [
+            Phase1Node::from(cube).get_depth_bound() as usize,
+            Phase2Node::from(cube).get_depth_bound() as usize,
+            Phase3Node::from(cube).get_depth_bound() as usize,
+        ]
+    }
+
     /// Returns an iterator which non-deterministically returns increasingly shorter solutions
     pub fn fast_solutions(&self, max_solution_len: Option<usize>) -> FastSolutionIterator {
         FastSolutionIterator::new(self.0, max_solution_len)
     }
 
+    /// Returns an iterator which non-deterministically returns increasingly better solutions by
+    /// length and then by cost
+    ///
+    /// Each solution is shorter than the last one, or has the same length and a lower cost.
+    pub fn fast_solutions_by_cost(
+        &self,
+        max_solution_len: Option<usize>,
+        cost_model: CostModel,
+    ) -> FastSolutionIterator {
+        FastSolutionIterator::with_cost_model(self.0, max_solution_len, Some(cost_model))
+    }
+
     /// Returns an iterator over all solutions to this cube in order of increasing length
     pub fn solutions(&self, solution_lengths: impl RangeBounds<usize>) -> ShortestSolutionIterator {
         ShortestSolutionIterator::new(CubieCube::from_cube(self.0), solution_lengths)
     }
 
+    /// Returns an iterator over all solutions to this cube in order of increasing length which
+    /// searches each length on every thread
+    ///
+    /// If `deterministic` is set, solutions of the same length are returned in the same order as
+    /// [`Cube::solutions`] returns them, which holds back solutions until the solutions before
+    /// them are found.
+    pub fn parallel_solutions(
+        &self,
+        solution_lengths: impl RangeBounds<usize>,
+        deterministic: bool,
+    ) -> ParallelSolutionIterator {
+        ParallelSolutionIterator::new(
+            CubieCube::from_cube(self.0),
+            solution_lengths,
+            deterministic,
+        )
+    }
+
+    /// Returns an iterator over solutions to this cube which continues a search from the checkpoint
+    ///
+    /// Returns an error if the checkpoint is not a position of a search for solutions to this cube.
+    pub fn resume_solutions(
+        &self,
+        checkpoint: &SearchCheckpoint,
+    ) -> Result<ShortestSolutionIterator, CheckpointError> {
+        ShortestSolutionIterator::resume(CubieCube::from_cube(self.0), checkpoint)
+    }
+
+    /// Returns the cube which is solved by exactly the sequences that take this cube to the target
+    /// with the rotations of both cubes removed
+    fn relative_to(&self, target: &Cube) -> Cube {
+        // a sequence takes this cube to the target if and only if it solves the cube which undoes
+        // the target and then does this cube
+        Cube(
+            target
+                .0
+                .without_rotation()
+                .inverse()
+                .then(self.0.without_rotation()),
+        )
+    }
+
+    /// Returns an iterator over all sequences which take this cube to the target up to rotation,
+    /// in order of increasing length
+    ///
+    /// The sequences are written in the frame of this cube, so doing a sequence on this cube gives
+    /// a cube which [is equivalent](#method.is_equivalent) to the target up to rotation.
+    pub fn solutions_to(
+        &self,
+        target: &Cube,
+        solution_lengths: impl RangeBounds<usize>,
+    ) -> RelativeSolutionIterator<ShortestSolutionIterator> {
+        RelativeSolutionIterator::new(
+            self.relative_to(target).solutions(solution_lengths),
+            self.0.rotation(),
+        )
+    }
+
+    /// Returns an iterator which non-deterministically returns increasingly shorter sequences
+    /// which take this cube to the target up to rotation
+    ///
+    /// See [`Cube::solutions_to`] and [`Cube::fast_solutions`].
+    pub fn fast_solutions_to(
+        &self,
+        target: &Cube,
+        max_solution_len: Option<usize>,
+    ) -> RelativeSolutionIterator<FastSolutionIterator> {
+        RelativeSolutionIterator::new(
+            self.relative_to(target).fast_solutions(max_solution_len),
+            self.0.rotation(),
+        )
+    }
+
+    /// Returns an iterator over all sequences which take this cube to the goal in order of
+    /// increasing length
+    ///
+    /// The sequences never twist the LDBO piece and are written in the frame of this cube like
+    /// [`Cube::solutions_to`].
+    pub fn alg_search(
+        &self,
+        goal: &GoalMask,
+        solution_lengths: impl RangeBounds<usize>,
+    ) -> AlgSearchIterator {
+        AlgSearchIterator::new(self.0, goal, solution_lengths)
+    }
+
     /// Returns an iterator over all solutions to this cube with the given length
     pub fn solutions_with_len(&self, solution_length: usize) -> FixedLengthSolutionIterator {
         FixedLengthSolutionIterator::new(CubieCube::from_cube(self.0), solution_length)
@@ -85,3 +260,41 @@ pub fn new_scramble(cube_index: CubeIndex) -> TwistSequence {
 
     simple_solve(cube).into_iter().map(|m| *m.twist()).collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::twists;
+
+    #[test]
+    fn test_solutions_to() {
+        let start = Cube::SOLVED.twist_seq(twists("RO {1-2}UF IF2"));
+        let target = Cube::SOLVED.twist_seq(twists("RO {1-2}FR IF2 UF"));
+
+        let solutions = start.solutions_to(&target, ..=4).collect::<Vec<_>>();
+        assert!(!solutions.is_empty());
+        assert!(solutions.iter().all(|solution| solution.len() <= 4));
+
+        for solution in solutions {
+            assert!(start.twist_seq(solution).is_equivalent(&target, true));
+        }
+
+        for (solution, _) in start.fast_solutions_to(&target, None) {
+            assert!(start.twist_seq(solution).is_equivalent(&target, true));
+        }
+    }
+
+    #[test]
+    fn test_relative_to() {
+        // none of the twists move the LDBO piece, so neither cube is rotated
+        let start = Cube::SOLVED.twist_seq(twists("RO IF2 UB'"));
+        let path = twists("UF FR' RU2");
+        let target = start.twist_seq(path.clone());
+
+        assert_eq!(
+            start.relative_to(&target),
+            Cube::SOLVED.twist_seq(path.inverse())
+        );
+        assert_eq!(target.relative_to(&target), Cube::SOLVED);
+    }
+}
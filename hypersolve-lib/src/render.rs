@@ -0,0 +1,468 @@
+use std::fmt::Write;
+
+use crate::*;
+
+/// Positions of the cells in the net view
+const NET_LAYOUT: [[Option<Face>; 4]; 3] = [
+    [None, Some(Face::U), Some(Face::F), None],
+    [Some(Face::L), Some(Face::I), Some(Face::R), Some(Face::O)],
+    [None, Some(Face::D), Some(Face::B), None],
+];
+
+/// Opacity of stickers on pieces which are not highlighted
+const FADED_OPACITY: f64 = 0.2;
+
+/// Angle (in radians) the projected view is rotated by around the vertical axis
+const VIEW_YAW: f64 = 0.5;
+
+/// Angle (in radians) the projected view is tilted by towards the viewer
+const VIEW_PITCH: f64 = 0.45;
+
+/// How the cells of the cube are arranged in a rendered image
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RenderView {
+    /// A flat net of the eight cells with each cell drawn as its back layer beside its front layer
+    #[default]
+    Net,
+    /// A 3D projection of the cells around the `I` cell like the default view in MC4D
+    ///
+    /// The `O` cell surrounds the other cells in this projection so it is not drawn.
+    Projected,
+}
+
+/// Options for rendering the cube as an SVG image
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderOptions {
+    /// How the cells are arranged
+    pub view: RenderView,
+    /// Colors of the faces
+    pub color_scheme: ColorScheme,
+    /// Pieces to highlight given by their solved location. If any pieces are highlighted then all
+    /// other pieces are faded.
+    pub highlighted: Vec<PieceLocation>,
+    /// Size of a sticker in pixels
+    pub sticker_size: f64,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            view: RenderView::default(),
+            color_scheme: ColorScheme::default(),
+            highlighted: Vec::new(),
+            sticker_size: 20.0,
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Returns the SVG fill color of the face with its brightness scaled by the given amount
+    fn fill(&self, face: Face, brightness: f64) -> String {
+        self.color_scheme
+            .color(face)
+            .rgb
+            .scale(brightness)
+            .to_string()
+    }
+
+    /// Returns the opacity of stickers on the piece
+    fn opacity(&self, piece: PieceLocation) -> f64 {
+        if self.highlighted.is_empty() || self.highlighted.contains(&piece) {
+            1.0
+        } else {
+            FADED_OPACITY
+        }
+    }
+}
+
+/// A filled polygon in sticker units
+struct Polygon {
+    points: Vec<(f64, f64)>,
+    fill: String,
+    opacity: f64,
+}
+
+/// A text label in sticker units
+struct Label {
+    position: (f64, f64),
+    text: String,
+}
+
+/// A drawing of a single cube state in sticker units with its top left corner at the origin
+struct Drawing {
+    polygons: Vec<Polygon>,
+    labels: Vec<Label>,
+    width: f64,
+    height: f64,
+}
+
+impl Drawing {
+    /// Writes the drawing as SVG elements offset by the given amount in sticker units
+    fn write_svg(&self, svg: &mut String, offset: (f64, f64), options: &RenderOptions) {
+        let scale = options.sticker_size;
+        let point = |(x, y): (f64, f64)| ((x + offset.0) * scale, (y + offset.1) * scale);
+
+        for polygon in self.polygons.iter() {
+            let points = polygon
+                .points
+                .iter()
+                .map(|&p| {
+                    let (x, y) = point(p);
+                    format!("{:.2},{:.2}", x, y)
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            writeln!(
+                svg,
+                r#"<polygon points="{}" fill="{}" fill-opacity="{}" stroke="black" stroke-width="{:.2}" stroke-linejoin="round"/>"#,
+                points,
+                polygon.fill,
+                polygon.opacity,
+                scale / 20.0
+            )
+            .unwrap();
+        }
+
+        for label in self.labels.iter() {
+            let (x, y) = point(label.position);
+            writeln!(
+                svg,
+                r#"<text x="{:.2}" y="{:.2}" font-family="sans-serif" font-size="{:.2}" text-anchor="middle">{}</text>"#,
+                x,
+                y,
+                scale * 0.8,
+                label.text
+            )
+            .unwrap();
+        }
+    }
+}
+
+/// Returns the sticker at the given position in a cell as a facelet index and the location of
+/// the piece it is on
+///
+/// `layer` is 0 for the back layer and 1 for the front layer, `row` is 0 for the top row and 1 for
+/// the bottom row, and `column` is 0 for the left column and 1 for the right column.
+fn cell_sticker(cell: Face, layer: usize, row: usize, column: usize) -> (usize, [i32; 4]) {
+    let [x, y, z] = cell.basis();
+    let [dx, dy, dz] = [
+        column as i32 * 2 - 1,
+        1 - row as i32 * 2,
+        layer as i32 * 2 - 1,
+    ];
+
+    let mut location = cell.into_vector4();
+    for i in 0..4 {
+        location[i] += dx * x[i] + dy * y[i] + dz * z[i];
+    }
+
+    let index = cell as usize * 8 + layer * 4 + row * 2 + column;
+
+    (index, location)
+}
+
+/// Draws the cube as a net of its cells
+fn draw_net(cube: &Cube, options: &RenderOptions) -> Drawing {
+    let facelets = cube.facelets();
+    let pieces = cube.facelet_pieces();
+
+    let mut polygons = Vec::new();
+    let mut labels = Vec::new();
+
+    for (row_index, cells) in NET_LAYOUT.iter().enumerate() {
+        for (column_index, cell) in cells.iter().enumerate() {
+            let Some(cell) = *cell else {
+                continue;
+            };
+
+            let (x0, y0) = (
+                column_index as f64 * 5.5 + 0.5,
+                row_index as f64 * 3.5 + 1.0,
+            );
+
+            labels.push(Label {
+                position: (x0 + 2.25, y0 - 0.25),
+                text: cell.to_string(),
+            });
+
+            for layer in 0..2 {
+                for row in 0..2 {
+                    for column in 0..2 {
+                        let (index, _) = cell_sticker(cell, layer, row, column);
+                        let x = x0 + layer as f64 * 2.5 + column as f64;
+                        let y = y0 + row as f64;
+
+                        polygons.push(Polygon {
+                            points: vec![(x, y), (x + 1.0, y), (x + 1.0, y + 1.0), (x, y + 1.0)],
+                            fill: options.fill(facelets[index], 1.0),
+                            opacity: options.opacity(pieces[index]),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    Drawing {
+        polygons,
+        labels,
+        width: 22.0,
+        height: 11.0,
+    }
+}
+
+/// Rotates a point into the coordinates of the projected view
+///
+/// The x and y coordinates are the screen coordinates and the z coordinate points towards the
+/// viewer.
+fn view_rotate([x, y, z]: [f64; 3]) -> [f64; 3] {
+    let (x, z) = (
+        x * VIEW_YAW.cos() - z * VIEW_YAW.sin(),
+        x * VIEW_YAW.sin() + z * VIEW_YAW.cos(),
+    );
+    let (y, z) = (
+        y * VIEW_PITCH.cos() - z * VIEW_PITCH.sin(),
+        y * VIEW_PITCH.sin() + z * VIEW_PITCH.cos(),
+    );
+
+    [x, -y, z]
+}
+
+/// Draws the cube as a 3D projection of its cells with each sticker drawn as a small cube
+fn draw_projected(cube: &Cube, options: &RenderOptions) -> Drawing {
+    const HALF_SIZE: f64 = 0.35;
+
+    let facelets = cube.facelets();
+    let pieces = cube.facelet_pieces();
+
+    // position of each sticker in 3D
+    let mut stickers = Vec::new();
+    for cell in [
+        Face::R,
+        Face::L,
+        Face::U,
+        Face::D,
+        Face::F,
+        Face::B,
+        Face::I,
+    ] {
+        for layer in 0..2 {
+            for row in 0..2 {
+                for column in 0..2 {
+                    let (index, location) = cell_sticker(cell, layer, row, column);
+
+                    let mut center = [0, 1, 2].map(|i| location[i] as f64 * 0.5);
+                    if cell != Face::I {
+                        // push the cell outwards and move stickers near the I cell inwards
+                        let axis = cell.axis() as usize;
+                        center[axis] = location[axis] as f64 * (2.5 + 0.5 * location[3] as f64);
+                    }
+
+                    stickers.push((center, index));
+                }
+            }
+        }
+    }
+
+    // draw the stickers from back to front
+    stickers.sort_by(|(a, _), (b, _)| view_rotate(*a)[2].total_cmp(&view_rotate(*b)[2]));
+
+    let mut polygons = Vec::new();
+    for (center, index) in stickers {
+        for axis in 0..3 {
+            for sign in [-1.0, 1.0] {
+                let mut normal = [0.0; 3];
+                normal[axis] = sign;
+
+                let normal_z = view_rotate(normal)[2];
+                if normal_z <= 0.0 {
+                    continue;
+                }
+
+                // corners of the face in order around it
+                let [u, v] = [(axis + 1) % 3, (axis + 2) % 3];
+                let points = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+                    .map(|(a, b)| {
+                        let mut corner = center;
+                        corner[axis] += sign * HALF_SIZE;
+                        corner[u] += a * HALF_SIZE;
+                        corner[v] += b * HALF_SIZE;
+
+                        let [x, y, _] = view_rotate(corner);
+                        (x, y)
+                    })
+                    .to_vec();
+
+                polygons.push(Polygon {
+                    points,
+                    fill: options.fill(facelets[index], 0.55 + 0.45 * normal_z),
+                    opacity: options.opacity(pieces[index]),
+                });
+            }
+        }
+    }
+
+    // move the drawing so that its top left corner is at the origin
+    let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+    let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for &(x, y) in polygons.iter().flat_map(|polygon| polygon.points.iter()) {
+        (min_x, min_y) = (min_x.min(x), min_y.min(y));
+        (max_x, max_y) = (max_x.max(x), max_y.max(y));
+    }
+
+    for polygon in polygons.iter_mut() {
+        for (x, y) in polygon.points.iter_mut() {
+            (*x, *y) = (*x - min_x + 0.5, *y - min_y + 0.5);
+        }
+    }
+
+    Drawing {
+        polygons,
+        labels: Vec::new(),
+        width: max_x - min_x + 1.0,
+        height: max_y - min_y + 1.0,
+    }
+}
+
+/// Draws the cube using the view in the options
+fn draw(cube: &Cube, options: &RenderOptions) -> Drawing {
+    match options.view {
+        RenderView::Net => draw_net(cube, options),
+        RenderView::Projected => draw_projected(cube, options),
+    }
+}
+
+/// Returns the opening tag of an SVG image with the given size in sticker units
+fn svg_header(width: f64, height: f64, options: &RenderOptions) -> String {
+    let (width, height) = (width * options.sticker_size, height * options.sticker_size);
+
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{:.0}" height="{:.0}" viewBox="0 0 {:.2} {:.2}">"#,
+        width.ceil(),
+        height.ceil(),
+        width,
+        height
+    ) + "\n"
+}
+
+impl Cube {
+    /// Renders the cube as an SVG image
+    pub fn render_svg(&self, options: &RenderOptions) -> String {
+        let drawing = draw(self, options);
+
+        let mut svg = svg_header(drawing.width, drawing.height, options);
+        drawing.write_svg(&mut svg, (0.0, 0.0), options);
+        svg.push_str("</svg>\n");
+
+        svg
+    }
+
+    /// Renders the cube after each twist of the sequence as a strip of SVG images from left to
+    /// right, starting with the cube itself
+    ///
+    /// Each image is labelled with the twist that was applied, formatted in the given notation.
+    pub fn render_strip_svg(
+        &self,
+        twists: &TwistSequence,
+        notation: Notation,
+        options: &RenderOptions,
+    ) -> String {
+        let mut cube = *self;
+        let mut frames = vec![(draw(&cube, options), String::new())];
+
+        for twist in twists.iter() {
+            cube = cube.twist(*twist);
+            frames.push((draw(&cube, options), notation.format_twist(twist)));
+        }
+
+        let width = frames.iter().map(|(drawing, _)| drawing.width).sum::<f64>();
+        let height = frames
+            .iter()
+            .map(|(drawing, _)| drawing.height)
+            .fold(0.0, f64::max)
+            + 1.5;
+
+        let mut svg = svg_header(width, height, options);
+        let mut x = 0.0;
+
+        for (mut drawing, label) in frames {
+            if !label.is_empty() {
+                drawing.labels.push(Label {
+                    position: (drawing.width / 2.0, height - 0.75),
+                    text: label,
+                });
+            }
+            drawing.write_svg(&mut svg, (x, 0.0), options);
+            x += drawing.width;
+        }
+        svg.push_str("</svg>\n");
+
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cell_sticker() {
+        for cell in [
+            Face::R,
+            Face::L,
+            Face::U,
+            Face::D,
+            Face::F,
+            Face::B,
+            Face::O,
+            Face::I,
+        ] {
+            for index in 0..8 {
+                let (layer, row, column) = (index / 4, index / 2 % 2, index % 2);
+                let (facelet, location) = cell_sticker(cell, layer, row, column);
+                assert_eq!(facelet, cell as usize * 8 + index);
+
+                // the sticker is on a piece in the cell
+                let vector = cell.into_vector4();
+                let axis = cell.axis() as usize;
+                assert_eq!(location[axis], vector[axis]);
+                assert!(location.iter().all(|c| c.abs() == 1));
+            }
+        }
+
+        // the stickers of the solved cube are colored by their cell
+        let facelets = Cube::SOLVED.facelets();
+        let pieces = Cube::SOLVED.facelet_pieces();
+        for (index, piece) in pieces.iter().enumerate() {
+            let (_, location) =
+                cell_sticker(facelets[index], index % 8 / 4, index % 4 / 2, index % 2);
+            assert_eq!(piece.0.map(|sign| sign as i32), location);
+        }
+    }
+
+    #[test]
+    fn test_render_svg() {
+        let cube = Cube::SOLVED.twist("RO".parse().unwrap());
+
+        for view in [RenderView::Net, RenderView::Projected] {
+            let options = RenderOptions {
+                view,
+                ..Default::default()
+            };
+
+            let svg = cube.render_svg(&options);
+            assert!(svg.starts_with("<svg") && svg.ends_with("</svg>\n"));
+
+            let strip = cube.render_strip_svg(
+                &"RO IF".parse::<TwistSequence>().unwrap(),
+                Notation::Standard,
+                &options,
+            );
+            assert_eq!(
+                strip.matches("<text").count() - svg.matches("<text").count() * 3,
+                2
+            );
+        }
+    }
+}
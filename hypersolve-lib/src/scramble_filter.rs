@@ -0,0 +1,187 @@
+use crate::*;
+
+/// Errors for finding scrambles which match a [`ScrambleFilter`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ScrambleFilterError {
+    #[error(transparent)]
+    Key(#[from] ScrambleKeyError),
+    #[error("no scramble matched the filter in {0} candidates")]
+    NoMatch(usize),
+}
+
+/// Requirements on the difficulty of a scramble
+///
+/// All bounds are inclusive and a bound of `None` doesn't restrict the scramble.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScrambleFilter {
+    /// Minimum length of the optimal solution
+    pub min_length: Option<usize>,
+    /// Maximum length of the optimal solution
+    pub max_length: Option<usize>,
+    /// Minimum depth of each phase, see [`Cube::phase_depths`]
+    pub min_phase_depths: [Option<usize>; 3],
+    /// Maximum depth of each phase, see [`Cube::phase_depths`]
+    pub max_phase_depths: [Option<usize>; 3],
+}
+
+/// A scramble found by [`ScrambleFilter::find`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilteredScramble {
+    /// The key of the matching candidate, which verifies like any other key
+    pub key: ScrambleKey,
+    /// The number of candidates which were rejected before this one
+    pub rejections: usize,
+    /// The index of the cube state the scramble is generated from
+    pub cube_index: CubeIndex,
+    /// The scramble
+    pub scramble: TwistSequence,
+}
+
+impl ScrambleFilter {
+    /// Returns whether the filter accepts every scramble
+    pub fn is_empty(&self) -> bool {
+        *self == ScrambleFilter::default()
+    }
+
+    /// Returns whether the cube matches the filter
+    ///
+    /// The phase depths are checked first since they are cheap. Checking the optimal solution
+    /// length can take a long time as bounds are computed until they decide whether the length is
+    /// in range.
+    pub fn matches(&self, cube: &Cube) -> bool {
+        let phase_depths = cube.phase_depths();
+
+        for (depth, (min, max)) in phase_depths
+            .into_iter()
+            .zip(self.min_phase_depths.into_iter().zip(self.max_phase_depths))
+        {
+            if min.is_some_and(|min| depth < min) || max.is_some_and(|max| depth > max) {
+                return false;
+            }
+        }
+
+        if self.min_length.is_none() && self.max_length.is_none() {
+            return true;
+        }
+
+        let min = self.min_length.unwrap_or(0);
+        let max = self.max_length.unwrap_or(usize::MAX);
+
+        for bound in cube.optimal_bounds() {
+            if bound.lower > max || bound.upper < min {
+                return false;
+            }
+            if bound.lower >= min && bound.upper <= max {
+                return true;
+            }
+        }
+
+        unreachable!("the bounds should meet at the optimal solution length")
+    }
+
+    /// Returns the candidate key with the given number derived from the key
+    ///
+    /// The first candidate is the key itself so that a filter which accepts every scramble gives
+    /// the same scramble as the key.
+    pub fn candidate_key(key: &ScrambleKey, candidate: usize) -> ScrambleKey {
+        match candidate {
+            0 => *key,
+            _ => key.derive(&format!("candidate {candidate}")),
+        }
+    }
+
+    /// Finds the first candidate key derived from the key whose scramble from the subset matches
+    /// the filter, trying at most `max_candidates` candidates
+    ///
+    /// Since candidates are derived in order, anyone with the key can check that the earlier
+    /// candidates were rejected rather than picked from.
+    pub fn find(
+        &self,
+        key: &ScrambleKey,
+        tag: &str,
+        subset: ScrambleSubset,
+        max_candidates: usize,
+    ) -> Result<FilteredScramble, ScrambleFilterError> {
+        for candidate in 0..max_candidates {
+            let key = Self::candidate_key(key, candidate);
+            let cube_index = key.to_subset_cube_index(tag, subset)?;
+            let scramble = new_scramble(cube_index);
+
+            if self.matches(&Cube::SOLVED.twist_seq(scramble.iter().copied())) {
+                return Ok(FilteredScramble {
+                    key,
+                    rejections: candidate,
+                    cube_index,
+                    scramble,
+                });
+            }
+        }
+
+        Err(ScrambleFilterError::NoMatch(max_candidates))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::twists;
+
+    const KEY: ScrambleKey = ScrambleKey::new(KeyVersion::V1, [3; 16]);
+
+    #[test]
+    fn test_empty_filter() {
+        let filter = ScrambleFilter::default();
+        assert!(filter.is_empty());
+
+        let scramble = filter.find(&KEY, "", ScrambleSubset::Full, 1).unwrap();
+        assert_eq!(scramble.key, KEY);
+        assert_eq!(scramble.rejections, 0);
+        assert_eq!(
+            scramble.scramble,
+            new_scramble(KEY.to_cube_index("").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_phase_depth_filter() {
+        let cube = Cube::SOLVED.twist_seq(new_scramble(KEY.to_cube_index("").unwrap()));
+        let depth = cube.phase_depths()[0];
+
+        let mut filter = ScrambleFilter::default();
+        filter.max_phase_depths[0] = Some(depth - 1);
+        assert!(!filter.matches(&cube));
+
+        let scramble = filter.find(&KEY, "", ScrambleSubset::Full, 1000).unwrap();
+        assert!(scramble.rejections > 0);
+        assert_eq!(
+            scramble.key,
+            ScrambleFilter::candidate_key(&KEY, scramble.rejections)
+        );
+        assert!(filter.matches(&Cube::SOLVED.twist_seq(scramble.scramble)));
+
+        // only states with every piece oriented have a phase 1 depth of 0
+        filter.max_phase_depths[0] = Some(0);
+        assert_eq!(
+            filter.find(&KEY, "", ScrambleSubset::Full, 3),
+            Err(ScrambleFilterError::NoMatch(3))
+        );
+    }
+
+    #[test]
+    fn test_length_filter() {
+        let cube = Cube::SOLVED.twist_seq(twists("RO UF"));
+
+        let filter = ScrambleFilter {
+            min_length: Some(2),
+            max_length: Some(3),
+            ..Default::default()
+        };
+        assert!(filter.matches(&cube));
+
+        let filter = ScrambleFilter {
+            min_length: Some(3),
+            ..Default::default()
+        };
+        assert!(!filter.matches(&cube));
+    }
+}
@@ -0,0 +1,397 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use sha2::{Digest, Sha256};
+
+use crate::{CubeIndex, ScrambleSubset, N_CUBE_STATES};
+
+/// Errors for parsing and using scramble keys
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ScrambleKeyError {
+    #[error("invalid character '{c}' at position {index}, valid characters are: 0...9, a...f")]
+    InvalidHexCharacter { c: char, index: usize },
+    #[error("invalid key length, expected 32 hexadecimal characters or 34 with a version byte")]
+    InvalidLength,
+    #[error("unknown key version {0}")]
+    UnknownVersion(u8),
+    #[error("version 0 keys don't support tags")]
+    TagNotSupported,
+    #[error("version 0 keys don't support subsets")]
+    SubsetNotSupported,
+}
+
+impl From<hex::FromHexError> for ScrambleKeyError {
+    fn from(value: hex::FromHexError) -> Self {
+        match value {
+            hex::FromHexError::InvalidHexCharacter { c, index } => {
+                Self::InvalidHexCharacter { c, index }
+            }
+            hex::FromHexError::InvalidStringLength | hex::FromHexError::OddLength => {
+                Self::InvalidLength
+            }
+        }
+    }
+}
+
+/// The scheme used to derive the cube state of a [`ScrambleKey`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum KeyVersion {
+    /// The original scheme: the first 128 bits of the SHA256 hash of the key bytes (as a little
+    /// endian integer) modulo [`N_CUBE_STATES`]
+    ///
+    /// This is slightly biased and doesn't support tags, but is kept so old keys still verify.
+    V0 = 0,
+    /// The hash of a domain separator, the key bytes, a counter and the tag is computed with
+    /// SHA256 for counters 0, 1, 2, ... and the first one whose low 92 bits (as a little endian
+    /// integer) are less than [`N_CUBE_STATES`] is the cube index
+    ///
+    /// Scrambles from a [`ScrambleSubset`] other than the full one use a domain separator
+    /// containing the name of the subset and keep the fewest bits that can hold every index
+    /// within the subset.
+    V1 = 1,
+}
+
+impl KeyVersion {
+    /// The version used for new keys
+    pub const LATEST: Self = KeyVersion::V1;
+}
+
+impl TryFrom<u8> for KeyVersion {
+    type Error = ScrambleKeyError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(KeyVersion::V0),
+            1 => Ok(KeyVersion::V1),
+            _ => Err(ScrambleKeyError::UnknownVersion(value)),
+        }
+    }
+}
+
+/// Domain separator for deriving cube indices from version 1 keys
+const V1_INDEX_DOMAIN: &str = "hypersolve scramble index v1\0";
+
+/// Domain separator for deriving keys from version 1 keys
+const V1_DERIVE_DOMAIN: &[u8] = b"hypersolve key derivation v1\0";
+
+/// A 128 bit verification key from which a scramble is generated
+///
+/// Keys are written as 32 hexadecimal characters prefixed by 2 hexadecimal characters for the
+/// version. Keys without a version are version 0 keys, which is the format of keys from before
+/// versions were introduced, so version 0 keys are always written without a version.
+///
+/// A tag such as the name of an event can be given when deriving the cube state so that the same
+/// key gives unrelated scrambles for different tags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScrambleKey {
+    version: KeyVersion,
+    bytes: [u8; 16],
+}
+
+impl Display for ScrambleKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.version {
+            KeyVersion::V0 => write!(f, "{}", hex::encode(self.bytes)),
+            version => write!(f, "{:02x}{}", version as u8, hex::encode(self.bytes)),
+        }
+    }
+}
+
+impl FromStr for ScrambleKey {
+    type Err = ScrambleKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = hex::decode(s)?;
+
+        match bytes.len() {
+            16 => Ok(ScrambleKey::new(KeyVersion::V0, bytes.try_into().unwrap())),
+            17 => Ok(ScrambleKey::new(
+                KeyVersion::try_from(bytes[0])?,
+                bytes[1..].try_into().unwrap(),
+            )),
+            _ => Err(ScrambleKeyError::InvalidLength),
+        }
+    }
+}
+
+impl ScrambleKey {
+    /// Creates a key with the given version and bytes
+    pub const fn new(version: KeyVersion, bytes: [u8; 16]) -> Self {
+        ScrambleKey { version, bytes }
+    }
+
+    /// Returns the version of the key
+    pub const fn version(&self) -> KeyVersion {
+        self.version
+    }
+
+    /// Returns the bytes of the key without the version
+    pub const fn bytes(&self) -> [u8; 16] {
+        self.bytes
+    }
+
+    /// Returns the index of the cube state the scramble of this key is generated from
+    ///
+    /// Returns an error if a tag is given for a version 0 key.
+    pub fn to_cube_index(&self, tag: &str) -> Result<CubeIndex, ScrambleKeyError> {
+        self.to_subset_cube_index(tag, ScrambleSubset::Full)
+    }
+
+    /// Returns the index of the cube state in the subset the scramble of this key is generated
+    /// from
+    ///
+    /// Returns an error if a tag or a subset other than the full one is given for a version 0 key.
+    pub fn to_subset_cube_index(
+        &self,
+        tag: &str,
+        subset: ScrambleSubset,
+    ) -> Result<CubeIndex, ScrambleKeyError> {
+        match self.version {
+            KeyVersion::V0 => {
+                if !tag.is_empty() {
+                    return Err(ScrambleKeyError::TagNotSupported);
+                }
+                if subset != ScrambleSubset::Full {
+                    return Err(ScrambleKeyError::SubsetNotSupported);
+                }
+
+                let hash = Sha256::digest(self.bytes);
+                let index = u128::from_le_bytes(hash[..16].try_into().unwrap()) % N_CUBE_STATES;
+
+                Ok(CubeIndex(index))
+            }
+            KeyVersion::V1 => {
+                let domain = match subset {
+                    ScrambleSubset::Full => V1_INDEX_DOMAIN.to_string(),
+                    _ => format!("hypersolve {} scramble index v1\0", subset.name()),
+                };
+
+                let n_states = subset.n_states();
+
+                // keep the fewest bits that can hold every index so that at least half of the
+                // candidates are accepted
+                let bits = 128 - n_states.leading_zeros();
+
+                for counter in 0_u32.. {
+                    let hash = Sha256::new()
+                        .chain_update(&domain)
+                        .chain_update(self.bytes)
+                        .chain_update(counter.to_le_bytes())
+                        .chain_update(tag.as_bytes())
+                        .finalize();
+
+                    let index =
+                        u128::from_le_bytes(hash[..16].try_into().unwrap()) & ((1 << bits) - 1);
+
+                    if index < n_states {
+                        return Ok(subset.cube_index(index));
+                    }
+                }
+
+                unreachable!("a hash should be accepted before the counter overflows")
+            }
+        }
+    }
+
+    /// Derives a new key with the same version from this key and the label
+    pub fn derive(&self, label: &str) -> Self {
+        let hash = match self.version {
+            KeyVersion::V0 => Sha256::new()
+                .chain_update(self.bytes)
+                .chain_update(label.as_bytes())
+                .finalize(),
+            KeyVersion::V1 => Sha256::new()
+                .chain_update(V1_DERIVE_DOMAIN)
+                .chain_update(self.bytes)
+                .chain_update(label.as_bytes())
+                .finalize(),
+        };
+
+        ScrambleKey::new(self.version, hash[..16].try_into().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cube indices of keys for each version and tag, which must never change
+    ///
+    /// Some of the version 1 vectors reject the first candidate index.
+    const INDEX_TEST_VECTORS: [(&str, &str, u128); 8] = [
+        (
+            "a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6",
+            "",
+            1834025091340869776184676728,
+        ),
+        (
+            "00000000000000000000000000000000",
+            "",
+            489782300474710955206919991,
+        ),
+        (
+            "01a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6",
+            "",
+            2307731228346839525642361060,
+        ),
+        (
+            "01a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6",
+            "example event 2024",
+            490924161133462827458958392,
+        ),
+        (
+            "0100000000000000000000000000000000",
+            "",
+            2614732591307089084485100785,
+        ),
+        (
+            "0100000000000000000000000000000000",
+            "round 1",
+            1098651850934687814987266015,
+        ),
+        (
+            "01ffffffffffffffffffffffffffffffff",
+            "",
+            832477199540781965897975787,
+        ),
+        (
+            "01ffffffffffffffffffffffffffffffff",
+            "round 1",
+            3209216007111220039326169208,
+        ),
+    ];
+
+    /// Cube indices of version 1 keys for each subset, which must never change
+    const SUBSET_TEST_VECTORS: [(&str, &str, ScrambleSubset, u128); 5] = [
+        (
+            "01a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6",
+            "",
+            ScrambleSubset::Orientation,
+            251987753216489761528243200,
+        ),
+        (
+            "01a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6",
+            "round 1",
+            ScrambleSubset::Permutation,
+            1939547223323018745,
+        ),
+        (
+            "01a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6",
+            "",
+            ScrambleSubset::Phase2,
+            2996112757326541008,
+        ),
+        (
+            "01a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6",
+            "round 1",
+            ScrambleSubset::Phase3,
+            95033622,
+        ),
+        (
+            "01a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6",
+            "",
+            ScrambleSubset::LastCell,
+            107803498813895500246915200,
+        ),
+    ];
+
+    /// Keys derived from keys and labels, which must never change
+    const DERIVE_TEST_VECTORS: [(&str, &str, &str); 4] = [
+        (
+            "a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6",
+            "round 1 scramble 1",
+            "d5af6d08043e7401f11484596a67b702",
+        ),
+        (
+            "00000000000000000000000000000000",
+            "round 2 extra 1",
+            "007d89404f3f0a5dfd02363738816f65",
+        ),
+        (
+            "01a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6",
+            "round 1 scramble 1",
+            "01e9b2ca5c909fbae1fb4eb730f3749276",
+        ),
+        (
+            "0100000000000000000000000000000000",
+            "round 2 extra 1",
+            "0163d19e3cfca3725d2ebeff8506df04fd",
+        ),
+    ];
+
+    #[test]
+    fn test_index_test_vectors() {
+        for (key, tag, index) in INDEX_TEST_VECTORS {
+            let key = key.parse::<ScrambleKey>().unwrap();
+            assert_eq!(
+                key.to_cube_index(tag),
+                Ok(CubeIndex(index)),
+                "{key} {tag:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_subset_test_vectors() {
+        for (key, tag, subset, index) in SUBSET_TEST_VECTORS {
+            let key = key.parse::<ScrambleKey>().unwrap();
+            assert_eq!(
+                key.to_subset_cube_index(tag, subset),
+                Ok(CubeIndex(index)),
+                "{key} {tag:?} {subset:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_derive_test_vectors() {
+        for (key, label, derived) in DERIVE_TEST_VECTORS {
+            let key = key.parse::<ScrambleKey>().unwrap();
+            assert_eq!(key.derive(label).to_string(), derived, "{key} {label:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_scramble_key() {
+        let key = "a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6"
+            .parse::<ScrambleKey>()
+            .unwrap();
+        assert_eq!(key.version(), KeyVersion::V0);
+        assert_eq!(key.to_string(), "a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6");
+
+        let key = "01a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6"
+            .parse::<ScrambleKey>()
+            .unwrap();
+        assert_eq!(key.version(), KeyVersion::V1);
+        assert_eq!(key.to_string(), "01a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6");
+
+        assert_eq!(
+            "ffa1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6".parse::<ScrambleKey>(),
+            Err(ScrambleKeyError::UnknownVersion(0xff))
+        );
+        assert_eq!(
+            "a1b2c3".parse::<ScrambleKey>(),
+            Err(ScrambleKeyError::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn test_tags() {
+        let v0 = ScrambleKey::new(KeyVersion::V0, [7; 16]);
+        assert!(v0.to_cube_index("").is_ok());
+        assert_eq!(
+            v0.to_cube_index("event"),
+            Err(ScrambleKeyError::TagNotSupported)
+        );
+        assert_eq!(
+            v0.to_subset_cube_index("", ScrambleSubset::Phase3),
+            Err(ScrambleKeyError::SubsetNotSupported)
+        );
+
+        let v1 = ScrambleKey::new(KeyVersion::V1, [7; 16]);
+        assert_ne!(v1.to_cube_index(""), v1.to_cube_index("event"));
+        assert_ne!(v1.to_cube_index(""), v0.to_cube_index(""));
+        assert_ne!(v1.derive("label"), v0.derive("label"));
+    }
+}
@@ -1,12 +1,14 @@
 use std::ops::{RangeInclusive, RangeToInclusive};
+use std::time::Instant;
 
 use hypersolve_core::{CubieCube, Move, TwistSequence};
 
 use crate::{Node, Phase1Node, Phase2Node, Phase3Node};
 
-use super::phase_solution_iterator::PhaseSolutionIterator;
+use super::phase_solution_iterator::{PhasePosition, PhaseSolutionIterator};
+use super::TimedOut;
 
-struct Phase23SolIter {
+pub(super) struct Phase23SolIter {
     total_sol_len: usize,
     phase2_sol_iter: PhaseSolutionIterator<Phase2Node, RangeToInclusive<usize>>,
     phase1_solved_cube: CubieCube,
@@ -15,7 +17,11 @@ struct Phase23SolIter {
 }
 
 impl Phase23SolIter {
-    fn new(previous_sol: Vec<Move>, start_cube: CubieCube, total_sol_len: usize) -> Self {
+    pub(super) fn new(
+        previous_sol: Vec<Move>,
+        start_cube: CubieCube,
+        total_sol_len: usize,
+    ) -> Self {
         let phase1_sol_len = previous_sol.len();
 
         Self {
@@ -27,6 +33,59 @@ impl Phase23SolIter {
             phase3_sol_iter: None,
         }
     }
+
+    /// Creates an iterator at the positions of the phase 2 and phase 3 iterators returned by
+    /// [`positions()`](#method.positions)
+    fn resume(
+        previous_sol: Vec<Move>,
+        start_cube: CubieCube,
+        total_sol_len: usize,
+        positions: &[PhasePosition],
+    ) -> Option<Self> {
+        let phase1_sol_len = previous_sol.len();
+
+        let (phase2_position, phase3_position) = match positions {
+            [phase2] => (phase2, None),
+            [phase2, phase3] => (phase2, Some(phase3)),
+            _ => return None,
+        };
+
+        let phase3_sol_iter = match phase3_position {
+            None => None,
+            Some(phase3_position) => {
+                let p2_sol = phase2_position.last_sol.clone()?;
+                let p2_solved_cube =
+                    start_cube.apply_moves(p2_sol.get(phase1_sol_len..)?.iter().copied());
+
+                Some(PhaseSolutionIterator::resume(
+                    p2_sol,
+                    Phase3Node::from(p2_solved_cube),
+                    total_sol_len..=total_sol_len,
+                    phase3_position.clone(),
+                )?)
+            }
+        };
+
+        Some(Self {
+            total_sol_len,
+            phase2_sol_iter: PhaseSolutionIterator::resume(
+                previous_sol,
+                Phase2Node::from(start_cube),
+                ..=total_sol_len,
+                phase2_position.clone(),
+            )?,
+            phase1_solved_cube: start_cube,
+            phase1_sol_len,
+            phase3_sol_iter,
+        })
+    }
+
+    /// Returns the positions of the phase 2 iterator and the phase 3 iterator if it exists
+    fn positions(&self) -> Vec<PhasePosition> {
+        std::iter::once(self.phase2_sol_iter.position())
+            .chain(self.phase3_sol_iter.as_ref().map(|iter| iter.position()))
+            .collect()
+    }
 }
 
 impl Iterator for Phase23SolIter {
@@ -87,19 +146,85 @@ impl FixedLengthSolutionIterator {
 
         self.phase23_sol_iter = None;
     }
-}
 
-impl Iterator for FixedLengthSolutionIterator {
-    type Item = TwistSequence;
-    fn next(&mut self) -> Option<Self::Item> {
+    /// Creates an iterator at the positions of the phase iterators returned by
+    /// [`positions()`](#method.positions)
+    ///
+    /// Returns `None` if the iterator could never be at the positions.
+    pub(crate) fn resume(
+        cube: CubieCube,
+        solution_length: usize,
+        positions: &[PhasePosition],
+    ) -> Option<Self> {
+        let (phase1_position, phase23_positions) = positions.split_first()?;
+
+        let phase23_sol_iter = match phase23_positions {
+            [] => None,
+            _ => {
+                let p1_sol = phase1_position.last_sol.clone()?;
+                let p1_solved_cube = cube.apply_moves(p1_sol.iter().copied());
+
+                Some(Phase23SolIter::resume(
+                    p1_sol,
+                    p1_solved_cube,
+                    solution_length,
+                    phase23_positions,
+                )?)
+            }
+        };
+
+        Some(Self {
+            solution_length,
+            init_cube: cube,
+            phase1_sol_iter: PhaseSolutionIterator::resume(
+                Vec::new(),
+                Phase1Node::from(cube),
+                ..=solution_length,
+                phase1_position.clone(),
+            )?,
+            phase23_sol_iter,
+        })
+    }
+
+    /// Returns the positions of the iterators of each phase which has started, starting with
+    /// phase 1
+    pub(crate) fn positions(&self) -> Vec<PhasePosition> {
+        std::iter::once(self.phase1_sol_iter.position())
+            .chain(
+                self.phase23_sol_iter
+                    .iter()
+                    .flat_map(|iter| iter.positions()),
+            )
+            .collect()
+    }
+
+    /// Returns the next solution, or an error if the deadline passes before it is found
+    ///
+    /// The deadline is checked before each phase 1 solution is extended, except the first one
+    /// after the call, so the search can continue a little past it and every call makes progress.
+    /// After an error the search can be continued by calling this again.
+    pub(crate) fn next_before(
+        &mut self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<TwistSequence>, TimedOut> {
+        let mut extended = false;
+
         loop {
             if let Some(p23_sol_iter) = self.phase23_sol_iter.as_mut() {
                 if let Some(sol) = p23_sol_iter.next() {
-                    return Some(sol.into_iter().map(|m| *m.twist()).collect());
+                    return Ok(Some(sol.into_iter().map(|m| *m.twist()).collect()));
                 }
+                self.phase23_sol_iter = None;
+            }
+
+            if extended && deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Err(TimedOut);
             }
+            extended = true;
 
-            let p1_sol = self.phase1_sol_iter.next()?;
+            let Some(p1_sol) = self.phase1_sol_iter.next() else {
+                return Ok(None);
+            };
 
             let p1_solved_cube = self.init_cube.apply_moves(p1_sol.iter().copied());
 
@@ -111,3 +236,45 @@ impl Iterator for FixedLengthSolutionIterator {
         }
     }
 }
+
+impl Iterator for FixedLengthSolutionIterator {
+    type Item = TwistSequence;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_before(None)
+            .expect("a search without a deadline should not time out")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hypersolve_core::{Cube, Notation};
+
+    use super::*;
+
+    #[test]
+    fn test_resume_from_positions() {
+        let cube = CubieCube::from_cube(
+            Cube::SOLVED.twists(
+                Notation::Standard
+                    .parse_twist_sequence("RO2 UF2 IF2 FR2")
+                    .unwrap()
+                    .inverse(),
+            ),
+        );
+
+        let solutions = FixedLengthSolutionIterator::new(cube, 4).collect::<Vec<_>>();
+        assert!(solutions.len() > 2);
+
+        for skipped in 0..=solutions.len() {
+            let mut sols = FixedLengthSolutionIterator::new(cube, 4);
+            for _ in 0..skipped {
+                sols.next();
+            }
+
+            let mut resumed =
+                FixedLengthSolutionIterator::resume(cube, 4, &sols.positions()).unwrap();
+            assert_eq!(resumed.next(), sols.next());
+            assert_eq!(resumed.collect::<Vec<_>>(), sols.collect::<Vec<_>>());
+        }
+    }
+}
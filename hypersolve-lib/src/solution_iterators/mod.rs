@@ -1,8 +1,39 @@
+use std::ops::{Bound, RangeBounds};
+
 mod fixed_length_solution_iterator;
 mod next_move_filter;
+mod parallel_solution_iter;
 mod phase_solution_iterator;
+mod relative_solution_iter;
+mod search_checkpoint;
 mod shortest_solution_iter;
 
 pub use fixed_length_solution_iterator::FixedLengthSolutionIterator;
+pub use parallel_solution_iter::ParallelSolutionIterator;
 pub(crate) use phase_solution_iterator::PhaseSolutionIterator;
+pub use relative_solution_iter::RelativeSolutionIterator;
+pub use search_checkpoint::{CheckpointError, PhaseCheckpoint, SearchCheckpoint};
 pub use shortest_solution_iter::ShortestSolutionIterator;
+
+/// Returned by searches which stop when a deadline passes before they finish
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("the deadline passed before the search finished")]
+pub struct TimedOut;
+
+/// Returns the shortest solution length in the range and the length which a search of the range
+/// stops at without searching it
+pub(crate) fn length_bounds(solution_lengths: impl RangeBounds<usize>) -> (usize, usize) {
+    let min_sol_len = match solution_lengths.start_bound() {
+        Bound::Included(&inc) => inc,
+        Bound::Excluded(&exc) => exc + 1,
+        Bound::Unbounded => 0,
+    };
+
+    let sol_len_limit = match solution_lengths.end_bound() {
+        Bound::Included(&inc) => inc + 1,
+        Bound::Excluded(&exc) => exc,
+        Bound::Unbounded => usize::MAX,
+    };
+
+    (min_sol_len, sol_len_limit)
+}
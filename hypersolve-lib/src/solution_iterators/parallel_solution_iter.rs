@@ -0,0 +1,302 @@
+use std::collections::BTreeMap;
+use std::ops::RangeBounds;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, sync_channel, Receiver, RecvTimeoutError, SyncSender};
+use std::sync::Arc;
+use std::time::Instant;
+
+use hypersolve_core::{CubieCube, Move, TwistSequence};
+use rayon::iter::{ParallelBridge, ParallelIterator};
+
+use crate::{Node, Phase1Node};
+
+use super::fixed_length_solution_iterator::Phase23SolIter;
+use super::{length_bounds, TimedOut};
+
+/// Searches for solutions of each length in the range on the global rayon pool, sending them back
+/// via `solutions`
+///
+/// The phase 1 solutions are taken one at a time by whichever thread is free, so a branch with
+/// many solutions doesn't hold up the others.
+fn parallel_solve(
+    cube: CubieCube,
+    min_sol_len: usize,
+    sol_len_limit: Arc<AtomicUsize>,
+    deterministic: bool,
+    stop_flag: Arc<AtomicBool>,
+    solutions: SyncSender<TwistSequence>,
+) {
+    for sol_len in min_sol_len.. {
+        // the limit can be lowered while searching
+        if sol_len >= sol_len_limit.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let branch_solutions = |p1_sol: Vec<Move>| {
+            let p1_solved_cube = cube.apply_moves(p1_sol.iter().copied());
+
+            Phase23SolIter::new(p1_sol, p1_solved_cube, sol_len).map(|sol| {
+                sol.into_iter()
+                    .map(|m| *m.twist())
+                    .collect::<TwistSequence>()
+            })
+        };
+
+        // no more branches are started once the search is stopped or the length is past the limit
+        let phase1_sols = Phase1Node::from(cube)
+            .phase_solutions(Vec::new(), ..=sol_len)
+            .take_while(|_| {
+                !stop_flag.load(Ordering::Relaxed)
+                    && sol_len < sol_len_limit.load(Ordering::Relaxed)
+            });
+
+        if deterministic {
+            let (branch_send, branch_receive) = channel();
+
+            std::thread::scope(|scope| {
+                scope.spawn(|| {
+                    phase1_sols.enumerate().par_bridge().for_each_with(
+                        branch_send,
+                        |branch_send, (i, p1_sol)| {
+                            // the branches are only dropped once the search is stopped
+                            let _ = branch_send.send((i, branch_solutions(p1_sol).collect()));
+                        },
+                    );
+                });
+
+                // the branches finish out of order, so they are held back until the branches
+                // before them are done to keep the solutions in the order of their branches
+                let mut finished = BTreeMap::<usize, Vec<_>>::new();
+                let mut next_branch = 0;
+
+                for (i, branch) in branch_receive {
+                    finished.insert(i, branch);
+
+                    while let Some(branch) = finished.remove(&next_branch) {
+                        next_branch += 1;
+
+                        for solution in branch {
+                            // stop if the iterator was dropped
+                            if solutions.send(solution).is_err() {
+                                stop_flag.store(true, Ordering::Relaxed);
+                                return;
+                            }
+                        }
+                    }
+                }
+            });
+        } else {
+            phase1_sols
+                .par_bridge()
+                .for_each_with(solutions.clone(), |solutions, p1_sol| {
+                    for solution in branch_solutions(p1_sol) {
+                        if stop_flag.load(Ordering::Relaxed) {
+                            return;
+                        }
+
+                        // stop if the iterator was dropped
+                        if solutions.send(solution).is_err() {
+                            stop_flag.store(true, Ordering::Relaxed);
+                            return;
+                        }
+                    }
+                });
+        }
+
+        if stop_flag.load(Ordering::Relaxed) {
+            return;
+        }
+    }
+}
+
+/// An iterator over solutions to a cube in order of increasing length which searches on every
+/// thread of the global rayon pool
+///
+/// The search of each length is split into branches by the phase 1 solution which each solution
+/// starts with, and the branches are searched in parallel. Solutions of the same length are
+/// returned in the order they are found, unless the iterator is deterministic, in which case they
+/// are returned in the same order as [`ShortestSolutionIterator`](super::ShortestSolutionIterator)
+/// returns them.
+pub struct ParallelSolutionIterator {
+    cube: CubieCube,
+    sol_len_limit: Arc<AtomicUsize>,
+    deterministic: bool,
+    sol_receive: Receiver<TwistSequence>,
+    stop_flag: Arc<AtomicBool>,
+}
+
+impl ParallelSolutionIterator {
+    pub(crate) fn new(
+        cube: CubieCube,
+        solution_lengths: impl RangeBounds<usize>,
+        deterministic: bool,
+    ) -> Self {
+        let (min_sol_len, sol_len_limit) = length_bounds(solution_lengths);
+        let sol_len_limit = Arc::new(AtomicUsize::new(sol_len_limit));
+
+        let (sol_receive, stop_flag) =
+            Self::spawn(cube, min_sol_len, sol_len_limit.clone(), deterministic);
+
+        Self {
+            cube,
+            sol_len_limit,
+            deterministic,
+            sol_receive,
+            stop_flag,
+        }
+    }
+
+    /// Starts searching from the given length on another thread
+    fn spawn(
+        cube: CubieCube,
+        min_sol_len: usize,
+        sol_len_limit: Arc<AtomicUsize>,
+        deterministic: bool,
+    ) -> (Receiver<TwistSequence>, Arc<AtomicBool>) {
+        let (sol_send, sol_receive) = sync_channel(0);
+        let stop_flag = Arc::new(AtomicBool::new(false));
+
+        let c_stop_flag = stop_flag.clone();
+        std::thread::spawn(move || {
+            parallel_solve(
+                cube,
+                min_sol_len,
+                sol_len_limit,
+                deterministic,
+                c_stop_flag,
+                sol_send,
+            )
+        });
+
+        (sol_receive, stop_flag)
+    }
+
+    /// Sets the iterator to begin outputing solutions of the given length
+    pub fn set_to_length(&mut self, solution_length: usize) {
+        self.stop_flag.store(true, Ordering::Relaxed);
+
+        (self.sol_receive, self.stop_flag) = Self::spawn(
+            self.cube,
+            solution_length,
+            self.sol_len_limit.clone(),
+            self.deterministic,
+        );
+    }
+
+    /// Stops the search after the solutions of the given length, unless it already stops sooner
+    pub fn limit_length(&mut self, max_solution_length: usize) {
+        self.sol_len_limit
+            .fetch_min(max_solution_length + 1, Ordering::Relaxed);
+    }
+
+    /// Returns the next solution, or an error if the deadline passes before it is found
+    ///
+    /// After an error the search can be continued by calling this again.
+    pub fn next_before(
+        &mut self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<TwistSequence>, TimedOut> {
+        let solution = match deadline {
+            None => self.sol_receive.recv().ok(),
+            Some(deadline) => match self
+                .sol_receive
+                .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            {
+                Ok(solution) => Some(solution),
+                Err(RecvTimeoutError::Disconnected) => None,
+                Err(RecvTimeoutError::Timeout) => return Err(TimedOut),
+            },
+        };
+
+        match solution {
+            // the next length may have been started before the limit was lowered
+            Some(solution) if solution.len() >= self.sol_len_limit.load(Ordering::Relaxed) => {
+                self.stop_flag.store(true, Ordering::Relaxed);
+                Ok(None)
+            }
+            solution => Ok(solution),
+        }
+    }
+}
+
+impl Drop for ParallelSolutionIterator {
+    fn drop(&mut self) {
+        // stop the search once the branches being searched are done
+        self.stop_flag.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Iterator for ParallelSolutionIterator {
+    type Item = TwistSequence;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_before(None)
+            .expect("a search without a deadline should not time out")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hypersolve_core::{Cube, Notation};
+
+    use super::*;
+    use crate::ShortestSolutionIterator;
+
+    #[test]
+    fn test_parallel_solutions() {
+        let cube = CubieCube::from_cube(
+            Cube::SOLVED.twists(
+                Notation::Standard
+                    .parse_twist_sequence("RO2 UF2 IF2 FR2")
+                    .unwrap()
+                    .inverse(),
+            ),
+        );
+
+        let solutions = ShortestSolutionIterator::new(cube, ..=4).collect::<Vec<_>>();
+
+        let deterministic = ParallelSolutionIterator::new(cube, ..=4, true).collect::<Vec<_>>();
+        assert_eq!(deterministic, solutions);
+
+        let to_strings = |solutions: Vec<TwistSequence>| {
+            let mut strings = solutions
+                .iter()
+                .map(|solution| solution.to_notation(Notation::Standard))
+                .collect::<Vec<_>>();
+            strings.sort();
+            strings
+        };
+
+        let found = ParallelSolutionIterator::new(cube, ..=4, false).collect::<Vec<_>>();
+        assert_eq!(to_strings(found), to_strings(solutions));
+    }
+
+    #[test]
+    fn test_parallel_set_to_length() {
+        let cube = CubieCube::from_cube(
+            Cube::SOLVED.twists(Notation::Standard.parse_twist_sequence("RO2 UF2").unwrap()),
+        );
+
+        let mut solutions = ParallelSolutionIterator::new(cube, ..=4, true);
+        assert_eq!(solutions.next().map(|solution| solution.len()), Some(2));
+
+        solutions.set_to_length(4);
+        assert!(solutions.all(|solution| solution.len() == 4));
+    }
+
+    #[test]
+    fn test_parallel_limit_length() {
+        let cube = CubieCube::from_cube(
+            Cube::SOLVED.twists(Notation::Standard.parse_twist_sequence("RO2 UF2").unwrap()),
+        );
+
+        let mut solutions = ParallelSolutionIterator::new(cube, ..=4, true);
+        let first = solutions.next().unwrap();
+        solutions.limit_length(first.len());
+
+        let optimal = ShortestSolutionIterator::new(cube, ..=first.len()).collect::<Vec<_>>();
+        assert_eq!(
+            std::iter::once(first).chain(solutions).collect::<Vec<_>>(),
+            optimal
+        );
+    }
+}
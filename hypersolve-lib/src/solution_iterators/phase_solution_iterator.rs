@@ -9,6 +9,16 @@ use super::next_move_filter::FilterSolveableNextMove;
 #[allow(type_alias_bounds)]
 type NextMoveIter<N: Node> = FilterSolveableNextMove<N, NextMoveIterator<Phase1, N::Phase>>;
 
+/// The position of a [`PhaseSolutionIterator`]
+#[derive(Debug, Clone)]
+pub struct PhasePosition {
+    /// The length of the solutions being searched, including the previous solution
+    pub length: usize,
+    /// The last solution returned at this length, or `None` if the search of this length hasn't
+    /// started
+    pub last_sol: Option<Vec<Move>>,
+}
+
 pub struct PhaseSolutionIterator<N: Node, R> {
     stack: Vec<Move>,
     next_move_stack: Vec<NextMoveIter<N>>,
@@ -44,6 +54,69 @@ where
         }
     }
 
+    /// Creates an iterator at the position returned by [`position()`](#method.position)
+    ///
+    /// Returns `None` if the iterator could never be at the position.
+    pub fn resume(
+        previous_sol: Vec<Move>,
+        start_node: N,
+        sol_len_range: R,
+        position: PhasePosition,
+    ) -> Option<Self> {
+        let mut iter = Self::new(previous_sol, start_node, sol_len_range);
+
+        if position.length < iter.total_sol_len {
+            return None;
+        }
+        iter.total_sol_len = position.length;
+
+        let Some(last_sol) = position.last_sol else {
+            return Some(iter);
+        };
+
+        let is_prefix = iter
+            .stack
+            .iter()
+            .zip(&last_sol)
+            .all(|(a, b)| a.into_u8() == b.into_u8());
+        if last_sol.len() != position.length || !is_prefix {
+            return None;
+        }
+
+        // rebuild the iterators which lead to the last solution
+        let mut current_node = start_node;
+        for &target in &last_sol[iter.stack.len()..] {
+            let mut new_iter = NextMoveIter::new(
+                current_node,
+                iter.stack
+                    .iter()
+                    .copied()
+                    .next_moves(iter.stack.len() + 1 == position.length),
+                position.length - iter.stack.len(),
+            );
+
+            let (_, next_node) =
+                new_iter.find(|&(next_move, _)| next_move.into_u8() == target.into_u8())?;
+
+            current_node = next_node;
+            iter.next_move_stack.push(new_iter);
+            iter.stack.push(target);
+        }
+
+        iter.is_first_item = false;
+
+        Some(iter)
+    }
+
+    /// Returns the position of the iterator, which it can be resumed from with
+    /// [`resume()`](#method.resume)
+    pub fn position(&self) -> PhasePosition {
+        PhasePosition {
+            length: self.total_sol_len,
+            last_sol: (!self.is_first_item).then(|| self.stack.clone()),
+        }
+    }
+
     fn initialize_iter_stack(&mut self) -> Option<()> {
         if self.total_sol_len == self.stack.len() {
             debug_assert!(self.start_node == N::GOAL);
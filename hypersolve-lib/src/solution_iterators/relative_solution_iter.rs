@@ -0,0 +1,42 @@
+use hypersolve_core::{Rotation, TwistSequence};
+
+use crate::FastSolutionIterator;
+
+use super::ShortestSolutionIterator;
+
+/// An iterator over sequences which take a cube to a target up to rotation, returned by
+/// [`Cube::solutions_to`](crate::Cube::solutions_to) and
+/// [`Cube::fast_solutions_to`](crate::Cube::fast_solutions_to)
+///
+/// The solutions of the search are rotated into the frame of the cube.
+pub struct RelativeSolutionIterator<I> {
+    solutions: I,
+    rotation: Rotation,
+}
+
+impl<I> RelativeSolutionIterator<I> {
+    pub(crate) fn new(solutions: I, rotation: Rotation) -> Self {
+        Self {
+            solutions,
+            rotation,
+        }
+    }
+}
+
+impl Iterator for RelativeSolutionIterator<ShortestSolutionIterator> {
+    type Item = TwistSequence;
+    fn next(&mut self) -> Option<Self::Item> {
+        let solution = self.solutions.next()?;
+
+        Some(solution.transform_by(self.rotation))
+    }
+}
+
+impl Iterator for RelativeSolutionIterator<FastSolutionIterator> {
+    type Item = (TwistSequence, usize);
+    fn next(&mut self) -> Option<Self::Item> {
+        let (solution, length) = self.solutions.next()?;
+
+        Some((solution.transform_by(self.rotation), length))
+    }
+}
@@ -0,0 +1,74 @@
+use hypersolve_core::{Move, Phase1, TwistSequence};
+
+use super::phase_solution_iterator::PhasePosition;
+
+/// Errors for resuming a search from a [`SearchCheckpoint`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CheckpointError {
+    #[error("the checkpoint contains a twist which is not a move of the solver")]
+    InvalidTwist,
+    #[error("the checkpoint is not a position of a search for solutions to this cube")]
+    InvalidPosition,
+}
+
+/// The position of the search of one phase in a [`SearchCheckpoint`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhaseCheckpoint {
+    /// The length of the sequences solving the phase which are being searched, including the
+    /// moves of the previous phases
+    pub length: usize,
+    /// The last sequence solving the phase which was found at this length, including the moves of
+    /// the previous phases, or `None` if the search of this length hasn't started
+    pub last_sequence: Option<TwistSequence>,
+}
+
+/// The position of a [`ShortestSolutionIterator`](crate::ShortestSolutionIterator) which the
+/// search can be resumed from with [`Cube::resume_solutions`](crate::Cube::resume_solutions)
+///
+/// The search goes through the solutions of each phase in a fixed order, so its position is given
+/// by the last sequence found by each phase which has started.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchCheckpoint {
+    /// The length of the solutions being searched
+    pub solution_length: usize,
+    /// The length which the search stops at without searching it, or `None` if the search has no
+    /// limit
+    pub length_limit: Option<usize>,
+    /// The position of the search of each phase which has started, starting with phase 1
+    pub phases: Vec<PhaseCheckpoint>,
+}
+
+impl From<PhasePosition> for PhaseCheckpoint {
+    fn from(value: PhasePosition) -> Self {
+        PhaseCheckpoint {
+            length: value.length,
+            last_sequence: value
+                .last_sol
+                .map(|sol| sol.into_iter().map(|m| *m.twist()).collect()),
+        }
+    }
+}
+
+impl TryFrom<&PhaseCheckpoint> for PhasePosition {
+    type Error = CheckpointError;
+    fn try_from(value: &PhaseCheckpoint) -> Result<Self, Self::Error> {
+        let last_sol = match &value.last_sequence {
+            None => None,
+            Some(sequence) => Some(
+                sequence
+                    .iter()
+                    .map(|twist| {
+                        Move::<Phase1>::iter()
+                            .find(|m| m.twist() == twist)
+                            .ok_or(CheckpointError::InvalidTwist)
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+        };
+
+        Ok(PhasePosition {
+            length: value.length,
+            last_sol,
+        })
+    }
+}
@@ -1,8 +1,11 @@
 use std::ops::RangeBounds;
+use std::time::Instant;
 
 use hypersolve_core::{CubieCube, TwistSequence};
 
 use super::fixed_length_solution_iterator::FixedLengthSolutionIterator;
+use super::phase_solution_iterator::PhasePosition;
+use super::{length_bounds, CheckpointError, SearchCheckpoint, TimedOut};
 
 /// An iterator over solutions to a cube in order of increasing length
 pub struct ShortestSolutionIterator {
@@ -12,17 +15,7 @@ pub struct ShortestSolutionIterator {
 
 impl ShortestSolutionIterator {
     pub(crate) fn new(cube: CubieCube, solution_lengths: impl RangeBounds<usize>) -> Self {
-        let min_sol_len = match solution_lengths.start_bound() {
-            std::ops::Bound::Included(&inc) => inc,
-            std::ops::Bound::Excluded(&exc) => exc + 1,
-            std::ops::Bound::Unbounded => 0,
-        };
-
-        let sol_len_limit = match solution_lengths.end_bound() {
-            std::ops::Bound::Included(&inc) => inc + 1,
-            std::ops::Bound::Excluded(&exc) => exc,
-            std::ops::Bound::Unbounded => usize::MAX,
-        };
+        let (min_sol_len, sol_len_limit) = length_bounds(solution_lengths);
 
         Self {
             sol_len_limit,
@@ -30,40 +23,97 @@ impl ShortestSolutionIterator {
         }
     }
 
+    /// Creates an iterator at the position of the checkpoint
+    pub(crate) fn resume(
+        cube: CubieCube,
+        checkpoint: &SearchCheckpoint,
+    ) -> Result<Self, CheckpointError> {
+        let positions = checkpoint
+            .phases
+            .iter()
+            .map(PhasePosition::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            sol_len_limit: checkpoint.length_limit.unwrap_or(usize::MAX),
+            fixed_len_iter: FixedLengthSolutionIterator::resume(
+                cube,
+                checkpoint.solution_length,
+                &positions,
+            )
+            .ok_or(CheckpointError::InvalidPosition)?,
+        })
+    }
+
     /// Sets the iterator to begin outputing solutions of the given length
     pub fn set_to_length(&mut self, solution_length: usize) {
         self.fixed_len_iter.reset_to_len(solution_length)
     }
-}
 
-impl Iterator for ShortestSolutionIterator {
-    type Item = TwistSequence;
-    fn next(&mut self) -> Option<Self::Item> {
+    /// Stops the search after the solutions of the given length, unless it already stops sooner
+    pub fn limit_length(&mut self, max_solution_length: usize) {
+        self.sol_len_limit = self.sol_len_limit.min(max_solution_length + 1);
+    }
+
+    /// Returns the position of the search, which it can be resumed from with
+    /// [`Cube::resume_solutions`](crate::Cube::resume_solutions)
+    ///
+    /// Resuming from the checkpoint continues with the solution after the last solution returned.
+    pub fn checkpoint(&self) -> SearchCheckpoint {
+        SearchCheckpoint {
+            solution_length: self.fixed_len_iter.sol_len(),
+            length_limit: (self.sol_len_limit != usize::MAX).then_some(self.sol_len_limit),
+            phases: self
+                .fixed_len_iter
+                .positions()
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        }
+    }
+
+    /// Returns the next solution, or an error if the deadline passes before it is found
+    ///
+    /// The deadline is checked between the phase 1 solutions the search goes through, so the
+    /// search can continue a little past it. After an error the search can be continued by calling
+    /// this again, or saved with [`checkpoint()`](#method.checkpoint).
+    pub fn next_before(
+        &mut self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<TwistSequence>, TimedOut> {
         if self.fixed_len_iter.sol_len() >= self.sol_len_limit {
-            return None;
+            return Ok(None);
         }
 
         loop {
-            if let Some(sol) = self.fixed_len_iter.next() {
-                return Some(sol);
+            if let Some(sol) = self.fixed_len_iter.next_before(deadline)? {
+                return Ok(Some(sol));
             } else {
                 let sol_len = self.fixed_len_iter.sol_len();
                 if sol_len + 1 < self.sol_len_limit {
                     self.fixed_len_iter.reset_to_len(sol_len + 1);
                     continue;
                 } else {
-                    return None;
+                    return Ok(None);
                 }
             }
         }
     }
 }
 
+impl Iterator for ShortestSolutionIterator {
+    type Item = TwistSequence;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_before(None)
+            .expect("a search without a deadline should not time out")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use hypersolve_core::{Cube, CubieCube, Notation};
 
-    use super::ShortestSolutionIterator;
+    use super::*;
 
     #[test]
     fn test_optimal_solution() {
@@ -86,4 +136,107 @@ mod tests {
 
         assert!(sols.next().is_some())
     }
+
+    #[test]
+    fn test_resume_from_checkpoint() {
+        let cube = CubieCube::from_cube(
+            Cube::SOLVED.twists(
+                Notation::Standard
+                    .parse_twist_sequence("RO2 UF2 IF2 FR2")
+                    .unwrap()
+                    .inverse(),
+            ),
+        );
+
+        let solutions = ShortestSolutionIterator::new(cube, ..=4).collect::<Vec<_>>();
+        assert!(solutions.len() > 2);
+
+        for skipped in 0..=solutions.len() {
+            let mut sols = ShortestSolutionIterator::new(cube, ..=4);
+            for _ in 0..skipped {
+                sols.next();
+            }
+
+            let resumed = ShortestSolutionIterator::resume(cube, &sols.checkpoint()).unwrap();
+            assert_eq!(resumed.collect::<Vec<_>>(), solutions[skipped..]);
+        }
+
+        // resuming after the deadline passed in the middle of a search
+        let mut sols = ShortestSolutionIterator::new(cube, ..=4);
+        let mut found = Vec::new();
+        while let Ok(Some(solution)) = sols.next_before(Some(Instant::now())) {
+            found.push(solution);
+        }
+
+        let resumed = ShortestSolutionIterator::resume(cube, &sols.checkpoint()).unwrap();
+        found.extend(resumed);
+        assert_eq!(found, solutions);
+    }
+
+    #[test]
+    fn test_next_before_past_deadline() {
+        let cube = CubieCube::from_cube(
+            Cube::SOLVED.twists(
+                Notation::Standard
+                    .parse_twist_sequence("RO2 UF2 IF2 FR2")
+                    .unwrap()
+                    .inverse(),
+            ),
+        );
+
+        let solutions = ShortestSolutionIterator::new(cube, ..=4).collect::<Vec<_>>();
+
+        // every call makes progress, so calling again after each timeout finishes the search
+        let deadline = Instant::now();
+        let mut sols = ShortestSolutionIterator::new(cube, ..=4);
+        let mut found = Vec::new();
+        let mut timeouts = 0;
+        loop {
+            match sols.next_before(Some(deadline)) {
+                Ok(Some(solution)) => found.push(solution),
+                Ok(None) => break,
+                Err(TimedOut) => timeouts += 1,
+            }
+        }
+
+        assert!(timeouts > 0);
+        assert_eq!(found, solutions);
+    }
+
+    #[test]
+    fn test_limit_length() {
+        let cube = CubieCube::from_cube(
+            Cube::SOLVED.twists(Notation::Standard.parse_twist_sequence("RO2 UF2").unwrap()),
+        );
+
+        let mut sols = ShortestSolutionIterator::new(cube, ..=4);
+        let first = sols.next().unwrap();
+        sols.limit_length(first.len());
+
+        // the rest of the solutions of the first length are still returned
+        let optimal = ShortestSolutionIterator::new(cube, ..=first.len()).collect::<Vec<_>>();
+        assert_eq!(
+            std::iter::once(first).chain(sols).collect::<Vec<_>>(),
+            optimal
+        );
+    }
+
+    #[test]
+    fn test_invalid_checkpoint() {
+        let cube = CubieCube::from_cube(
+            Cube::SOLVED.twists(Notation::Standard.parse_twist_sequence("RO2 UF2").unwrap()),
+        );
+
+        let mut sols = ShortestSolutionIterator::new(cube, ..=4);
+        sols.next();
+        let checkpoint = sols.checkpoint();
+
+        let other_cube = CubieCube::from_cube(
+            Cube::SOLVED.twists(Notation::Standard.parse_twist_sequence("RO2 IF2").unwrap()),
+        );
+        assert_eq!(
+            ShortestSolutionIterator::resume(other_cube, &checkpoint).err(),
+            Some(CheckpointError::InvalidPosition)
+        );
+    }
 }
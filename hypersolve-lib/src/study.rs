@@ -0,0 +1,137 @@
+use std::time::{Duration, Instant};
+
+use hypersolve_core::CubieCube;
+
+use crate::solution_iterators::TimedOut;
+use crate::*;
+
+/// How [`study_state`] searches for the optimal solution length of a state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StudyMode {
+    /// Finds increasingly shorter solutions to lower the upper bound, taking the lower bound from
+    /// the phase 1 pruning table
+    Bounds,
+    /// Searches every solution length from the lower bound up until a solution is found, raising
+    /// the lower bound with each length that has no solutions
+    Optimal,
+}
+
+impl StudyMode {
+    /// All modes
+    pub const ALL: [Self; 2] = [StudyMode::Bounds, StudyMode::Optimal];
+
+    /// Returns the name of the mode
+    pub const fn name(&self) -> &'static str {
+        match self {
+            StudyMode::Bounds => "bounds",
+            StudyMode::Optimal => "optimal",
+        }
+    }
+}
+
+/// The bounds on the optimal solution length of a state found by [`study_state`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StudyResult {
+    /// The index of the state
+    pub cube_index: CubeIndex,
+    /// The bounds on the optimal solution length, which are equal if it was found
+    pub bound: Bound<usize>,
+    /// The time the search took
+    pub time: Duration,
+}
+
+impl StudyResult {
+    /// Returns the optimal solution length if it was found
+    pub fn exact(&self) -> Option<usize> {
+        (self.bound.lower == self.bound.upper).then_some(self.bound.lower)
+    }
+}
+
+/// Searches for the optimal solution length of the state which the scramble of the cube index
+/// results in, stopping when the time limit is reached
+///
+/// The time limit is checked between the steps of the search, so the search can take a little
+/// longer than the time limit.
+pub fn study_state(cube_index: CubeIndex, mode: StudyMode, time_limit: Duration) -> StudyResult {
+    // generating the scramble loads every table, so the time limit starts after it
+    let cube = Cube::SOLVED.twist_seq(new_scramble(cube_index));
+    let cubie_cube = CubieCube::from_cube(cube.0);
+
+    let start = Instant::now();
+    let deadline = start + time_limit;
+
+    let mut bound = Bound {
+        upper: GODS_NUMBER_UPPER_BOUND,
+        lower: Phase1Node::from(cubie_cube).get_depth_bound() as usize,
+    };
+
+    let mut fast_solutions = FastSolutionIterator::new(cube.0, None);
+
+    match mode {
+        StudyMode::Bounds => {
+            while bound.lower < bound.upper {
+                match fast_solutions.next_before(Some(deadline)) {
+                    Ok(Some((_, len))) => bound.upper = len,
+                    // there are no shorter solutions so the last solution is optimal
+                    Ok(None) => bound.lower = bound.upper,
+                    Err(TimedOut) => break,
+                }
+            }
+        }
+        StudyMode::Optimal => {
+            if let Ok(Some((_, len))) = fast_solutions.next_before(Some(deadline)) {
+                bound.upper = len;
+            }
+
+            while bound.lower < bound.upper {
+                match FixedLengthSolutionIterator::new(cubie_cube, bound.lower)
+                    .next_before(Some(deadline))
+                {
+                    Ok(Some(_)) => bound.upper = bound.lower,
+                    Ok(None) => bound.lower += 1,
+                    Err(TimedOut) => break,
+                }
+            }
+        }
+    }
+
+    StudyResult {
+        cube_index,
+        bound,
+        time: start.elapsed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_study_state() {
+        let scramble = Notation::Standard
+            .parse_twist_sequence("RO2 UF2 IF2 FR2")
+            .unwrap();
+        let cube_index = CubeIndex::from(CubieCube::from_cube(
+            Cube::SOLVED.twist_seq(scramble.inverse()).0,
+        ));
+
+        for mode in StudyMode::ALL {
+            let result = study_state(cube_index, mode, Duration::from_secs(600));
+
+            assert_eq!(result.cube_index, cube_index);
+            assert_eq!(result.exact(), Some(4), "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn test_study_time_limit() {
+        let cube_index = ScrambleKey::new(KeyVersion::V1, [5; 16])
+            .to_cube_index("")
+            .unwrap();
+
+        let result = study_state(cube_index, StudyMode::Optimal, Duration::ZERO);
+
+        assert_eq!(result.exact(), None);
+        assert!(result.bound.lower < result.bound.upper);
+    }
+}
@@ -0,0 +1,256 @@
+use hypersolve_core::{
+    CubieCube, N_C3_COORD_STATES, N_IO_COORD_STATES, N_I_COORD_STATES, N_K4_COORD_STATES,
+    N_O_COORD_STATES,
+};
+
+use crate::{Cube, CubeIndex, Node, Phase3Node};
+
+/// A subgroup of cube states to generate scrambles from
+///
+/// Each subset fixes some of the coordinates the cube index is made of, so its states can be
+/// indexed and sampled uniformly like the full cube index. All subsets are closed under inversion,
+/// so the scramble of a state in a subset also results in a state in the subset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ScrambleSubset {
+    /// All states
+    #[default]
+    Full,
+    /// States with only the orientation of the pieces scrambled
+    Orientation,
+    /// States with only the permutation of the pieces scrambled
+    Permutation,
+    /// States in the subgroup that phase 2 solves, i.e. with phase 1 solved
+    Phase2,
+    /// States in the subgroup that phase 3 solves, i.e. with phases 1 and 2 solved
+    Phase3,
+    /// States with only the pieces of the O cell unsolved
+    LastCell,
+}
+
+/// How a coordinate is restricted in a subset
+#[derive(Clone, Copy)]
+enum CoordRange {
+    /// Any value
+    Free,
+    /// Only the solved value
+    Solved,
+    /// Only values which leave the pieces outside of the O cell solved
+    OCell,
+}
+
+/// A coordinate the cube index is made of
+struct Coord {
+    /// The number of values of the coordinate
+    n_states: u128,
+    /// The number of values which leave the pieces outside of the O cell solved
+    n_o_cell_states: u128,
+    /// The step between values which leave the pieces outside of the O cell solved
+    o_cell_step: u128,
+}
+
+impl Coord {
+    /// Returns the number of values and the step between values in the range
+    const fn range(&self, range: CoordRange) -> (u128, u128) {
+        match range {
+            CoordRange::Free => (self.n_states, 1),
+            CoordRange::Solved => (1, 1),
+            CoordRange::OCell => (self.n_o_cell_states, self.o_cell_step),
+        }
+    }
+}
+
+/// The coordinates of the cube index, from most to least significant
+///
+/// The O cell pieces are the last 7 pieces of the orientation coordinates, whose last C3 value is
+/// determined by the others. The phase 3 coordinate leaves the I cell solved for multiples of half
+/// the I coordinate states, which are exactly the even O cell permutations.
+const COORDS: [Coord; 4] = [
+    // K4 orientation (phase 1)
+    Coord {
+        n_states: N_K4_COORD_STATES as u128,
+        n_o_cell_states: 4_u128.pow(7),
+        o_cell_step: 4_u128.pow(8),
+    },
+    // IO separation (phase 2)
+    Coord {
+        n_states: N_IO_COORD_STATES as u128,
+        n_o_cell_states: 1,
+        o_cell_step: 1,
+    },
+    // C3 orientation (phase 2)
+    Coord {
+        n_states: N_C3_COORD_STATES as u128,
+        n_o_cell_states: 3_u128.pow(6),
+        o_cell_step: 3_u128.pow(8),
+    },
+    // I and O permutation (phase 3)
+    Coord {
+        n_states: Phase3Node::N_STATES as u128,
+        n_o_cell_states: N_O_COORD_STATES as u128 / 2,
+        o_cell_step: N_I_COORD_STATES as u128 / 2,
+    },
+];
+
+impl ScrambleSubset {
+    /// All subsets
+    pub const ALL: [Self; 6] = [
+        ScrambleSubset::Full,
+        ScrambleSubset::Orientation,
+        ScrambleSubset::Permutation,
+        ScrambleSubset::Phase2,
+        ScrambleSubset::Phase3,
+        ScrambleSubset::LastCell,
+    ];
+
+    /// Returns the name of the subset
+    pub const fn name(&self) -> &'static str {
+        match self {
+            ScrambleSubset::Full => "full",
+            ScrambleSubset::Orientation => "orientation",
+            ScrambleSubset::Permutation => "permutation",
+            ScrambleSubset::Phase2 => "phase 2",
+            ScrambleSubset::Phase3 => "phase 3",
+            ScrambleSubset::LastCell => "last cell",
+        }
+    }
+
+    /// Returns how each coordinate in [`COORDS`] is restricted
+    const fn ranges(&self) -> [CoordRange; 4] {
+        use CoordRange::*;
+
+        match self {
+            ScrambleSubset::Full => [Free, Free, Free, Free],
+            ScrambleSubset::Orientation => [Free, Solved, Free, Solved],
+            ScrambleSubset::Permutation => [Solved, Free, Solved, Free],
+            ScrambleSubset::Phase2 => [Solved, Free, Free, Free],
+            ScrambleSubset::Phase3 => [Solved, Solved, Solved, Free],
+            ScrambleSubset::LastCell => [OCell, OCell, OCell, OCell],
+        }
+    }
+
+    /// Returns the number of states in the subset
+    pub fn n_states(&self) -> u128 {
+        COORDS
+            .iter()
+            .zip(self.ranges())
+            .map(|(coord, range)| coord.range(range).0)
+            .product()
+    }
+
+    /// Returns the cube index of the state with the given index within the subset
+    ///
+    /// The index within the full subset is the cube index itself.
+    ///
+    /// # Panics
+    /// Panics if `index` is not less than [`n_states()`](#method.n_states)
+    pub fn cube_index(&self, mut index: u128) -> CubeIndex {
+        assert!(index < self.n_states(), "index out of range for the subset");
+
+        let mut values = [0; 4];
+        for ((coord, range), value) in COORDS.iter().zip(self.ranges()).zip(&mut values).rev() {
+            let (n_states, step) = coord.range(range);
+
+            *value = index % n_states * step;
+            index /= n_states;
+        }
+
+        let [k4, io, c3, p3] = values;
+
+        CubeIndex(
+            ((k4 * COORDS[1].n_states + io) * COORDS[2].n_states + c3) * COORDS[3].n_states + p3,
+        )
+    }
+
+    /// Returns whether the state with the given cube index is in the subset
+    pub fn contains_index(&self, cube_index: CubeIndex) -> bool {
+        let mut index = cube_index.0;
+
+        COORDS
+            .iter()
+            .zip(self.ranges())
+            .rev()
+            .all(|(coord, range)| {
+                let value = index % coord.n_states;
+                index /= coord.n_states;
+
+                let (n_states, step) = coord.range(range);
+                value.is_multiple_of(step) && value / step < n_states
+            })
+    }
+
+    /// Returns whether the cube is in the subset, ignoring the orientation of the whole cube
+    pub fn contains(&self, cube: &Cube) -> bool {
+        self.contains_index(CubeIndex::from(CubieCube::from_cube(cube.0)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{new_scramble, N_CUBE_STATES};
+
+    #[test]
+    fn test_full_subset() {
+        assert_eq!(ScrambleSubset::Full.n_states(), N_CUBE_STATES);
+
+        for index in [0, 1, 12345678987654321, N_CUBE_STATES - 1] {
+            assert_eq!(ScrambleSubset::Full.cube_index(index), CubeIndex(index));
+        }
+    }
+
+    #[test]
+    fn test_subset_sizes() {
+        assert_eq!(
+            ScrambleSubset::Orientation.n_states(),
+            N_K4_COORD_STATES as u128 * N_C3_COORD_STATES as u128
+        );
+        assert_eq!(
+            ScrambleSubset::Phase3.n_states(),
+            Phase3Node::N_STATES as u128
+        );
+        // 7 pieces with 12 orientations each, where the last orientation is determined by the
+        // others up to the K4 part, and the even permutations of 7 pieces
+        assert_eq!(
+            ScrambleSubset::LastCell.n_states(),
+            4_u128.pow(7) * 3_u128.pow(6) * 2520
+        );
+    }
+
+    #[test]
+    fn test_subset_scrambles() {
+        for subset in ScrambleSubset::ALL {
+            let n_states = subset.n_states();
+
+            for index in [0, 1, n_states / 3, n_states / 2 + 7, n_states - 1] {
+                let cube_index = subset.cube_index(index);
+                assert!(subset.contains_index(cube_index), "{subset:?} {index}");
+
+                let scramble = new_scramble(cube_index);
+                let cube = Cube::SOLVED.twist_seq(scramble);
+                assert!(subset.contains(&cube), "{subset:?} {index}");
+            }
+        }
+
+        let cube_index =
+            ScrambleSubset::Orientation.cube_index(ScrambleSubset::Orientation.n_states() - 1);
+        assert!(!ScrambleSubset::Permutation.contains_index(cube_index));
+        assert!(!ScrambleSubset::Phase2.contains_index(cube_index));
+    }
+
+    #[test]
+    fn test_last_cell_subset() {
+        let cube_index =
+            ScrambleSubset::LastCell.cube_index(ScrambleSubset::LastCell.n_states() / 3);
+        let cube = Cube::SOLVED.twist_seq(new_scramble(cube_index));
+
+        // the I cell pieces are the first 8 pieces
+        for (location, piece) in hypersolve_core::PieceLocation::ALL
+            .into_iter()
+            .zip(cube.0.pieces())
+            .take(8)
+        {
+            assert_eq!(*piece, location.solved_piece());
+        }
+        assert_ne!(cube, Cube::SOLVED);
+    }
+}
@@ -0,0 +1,86 @@
+use super::*;
+
+/// The result of checking a scramble against the scramble generated from a cube index
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScrambleVerification {
+    /// The scramble is exactly the generated scramble
+    Exact,
+    /// The scramble has different moves but results in the same state as the generated scramble,
+    /// e.g. because it was converted to MC4D notation, which splits double twists
+    SameState,
+    /// The scramble results in a different state
+    DifferentState,
+}
+
+impl ScrambleVerification {
+    /// Returns whether the scramble is valid, only accepting the exact generated scramble if
+    /// `strict` is true
+    pub fn is_valid(&self, strict: bool) -> bool {
+        match self {
+            ScrambleVerification::Exact => true,
+            ScrambleVerification::SameState => !strict,
+            ScrambleVerification::DifferentState => false,
+        }
+    }
+}
+
+/// Checks the scramble against the scramble generated from the cube index
+///
+/// The states are compared up to the orientation of the whole cube, so scrambles which rotate the
+/// cube or use twists of the other layer still result in the same state.
+pub fn verify_scramble(cube_index: CubeIndex, scramble: &TwistSequence) -> ScrambleVerification {
+    let expected = new_scramble(cube_index);
+
+    if *scramble == expected {
+        return ScrambleVerification::Exact;
+    }
+
+    let cube = Cube::SOLVED.twist_seq(scramble.iter().copied());
+    let expected_cube = Cube::SOLVED.twist_seq(expected);
+
+    match cube.is_equivalent(&expected_cube, true) {
+        true => ScrambleVerification::SameState,
+        false => ScrambleVerification::DifferentState,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_scramble() {
+        let cube_index = CubeIndex::try_from(N_CUBE_STATES / 3).unwrap();
+        let scramble = new_scramble(cube_index);
+
+        assert_eq!(
+            verify_scramble(cube_index, &scramble),
+            ScrambleVerification::Exact
+        );
+
+        let converted = Notation::MC4D
+            .parse_twist_sequence(&scramble.to_notation(Notation::MC4D))
+            .unwrap();
+        assert_eq!(
+            verify_scramble(cube_index, &converted),
+            ScrambleVerification::SameState
+        );
+
+        let mut rotated = scramble.clone();
+        rotated.0.push("{1-2}RO".parse().unwrap());
+        assert_eq!(
+            verify_scramble(cube_index, &rotated),
+            ScrambleVerification::SameState
+        );
+
+        let mut different = scramble;
+        different.0.push("RO".parse().unwrap());
+        assert_eq!(
+            verify_scramble(cube_index, &different),
+            ScrambleVerification::DifferentState
+        );
+
+        assert!(ScrambleVerification::SameState.is_valid(false));
+        assert!(!ScrambleVerification::SameState.is_valid(true));
+    }
+}
@@ -0,0 +1,463 @@
+use serde::{Deserialize, Serialize};
+use sha2::Digest;
+
+use super::*;
+
+/// Errors for reading and verifying scramble certificates
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum CertificateError {
+    #[error("invalid certificate file: {0}")]
+    InvalidFile(String),
+    #[error("the certificate contains no scramble")]
+    MissingScramble,
+    #[error("invalid verification key: {0}")]
+    InvalidKey(ScrambleKeyError),
+    #[error("unknown subset `{0}`")]
+    InvalidSubset(String),
+    #[error("invalid cube index `{0}`")]
+    InvalidCubeIndex(String),
+    #[error("invalid {notation} scramble: {error}")]
+    InvalidScramble {
+        notation: Notation,
+        error: ParseAlgorithmError,
+    },
+    #[error("the digest does not match the contents of the certificate")]
+    DigestMismatch,
+    #[error("the cube index {found} does not match the index {expected} derived from the key")]
+    IndexMismatch { found: String, expected: String },
+    #[error("the {0} scramble does not match the scramble generated from the cube index")]
+    ScrambleMismatch(Notation),
+    #[error("the key is not candidate {0} of the base key")]
+    CandidateMismatch(usize),
+    #[error("the scramble does not match the filter")]
+    FilterMismatch,
+    #[error("candidate {0} of the base key matches the filter but was rejected")]
+    RejectedMatch(usize),
+}
+
+/// The scramble of a [`ScrambleCertificate`] in each notation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CertificateScramble {
+    /// The scramble in standard notation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub standard: Option<String>,
+    /// The scramble in MC4D notation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mc4d: Option<String>,
+}
+
+impl CertificateScramble {
+    /// Returns the notation and text of each scramble which is present
+    fn notations(&self) -> impl Iterator<Item = (Notation, &str)> {
+        [
+            (Notation::Standard, &self.standard),
+            (Notation::MC4D, &self.mc4d),
+        ]
+        .into_iter()
+        .filter_map(|(notation, scramble)| Some((notation, scramble.as_deref()?)))
+    }
+}
+
+/// The filter the scramble of a [`ScrambleCertificate`] was chosen with
+///
+/// The scramble is the first candidate derived from the base key which matches the filter, so the
+/// base key and the number of rejected candidates show that the filter wasn't used to pick a
+/// scramble from several matching candidates.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CertificateFilter {
+    /// The key the candidate keys are derived from
+    pub base_key: String,
+    /// The number of candidates which were rejected before the key of the certificate
+    pub rejections: usize,
+    /// Minimum length of the optimal solution
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<usize>,
+    /// Maximum length of the optimal solution
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    /// Minimum depth of phase 1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_phase1_depth: Option<usize>,
+    /// Maximum depth of phase 1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_phase1_depth: Option<usize>,
+    /// Minimum depth of phase 2
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_phase2_depth: Option<usize>,
+    /// Maximum depth of phase 2
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_phase2_depth: Option<usize>,
+    /// Minimum depth of phase 3
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_phase3_depth: Option<usize>,
+    /// Maximum depth of phase 3
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_phase3_depth: Option<usize>,
+}
+
+impl CertificateFilter {
+    /// Creates the record of the filter and the candidates it rejected
+    pub fn new(base_key: &ScrambleKey, rejections: usize, filter: &ScrambleFilter) -> Self {
+        CertificateFilter {
+            base_key: base_key.to_string(),
+            rejections,
+            min_length: filter.min_length,
+            max_length: filter.max_length,
+            min_phase1_depth: filter.min_phase_depths[0],
+            max_phase1_depth: filter.max_phase_depths[0],
+            min_phase2_depth: filter.min_phase_depths[1],
+            max_phase2_depth: filter.max_phase_depths[1],
+            min_phase3_depth: filter.min_phase_depths[2],
+            max_phase3_depth: filter.max_phase_depths[2],
+        }
+    }
+
+    /// Returns the filter
+    pub fn filter(&self) -> ScrambleFilter {
+        ScrambleFilter {
+            min_length: self.min_length,
+            max_length: self.max_length,
+            min_phase_depths: [
+                self.min_phase1_depth,
+                self.min_phase2_depth,
+                self.min_phase3_depth,
+            ],
+            max_phase_depths: [
+                self.max_phase1_depth,
+                self.max_phase2_depth,
+                self.max_phase3_depth,
+            ],
+        }
+    }
+
+    /// Returns the name and value of each bound which is present
+    fn bounds(&self) -> impl Iterator<Item = (&'static str, usize)> {
+        [
+            ("min_length", self.min_length),
+            ("max_length", self.max_length),
+            ("min_phase1_depth", self.min_phase1_depth),
+            ("max_phase1_depth", self.max_phase1_depth),
+            ("min_phase2_depth", self.min_phase2_depth),
+            ("max_phase2_depth", self.max_phase2_depth),
+            ("min_phase3_depth", self.min_phase3_depth),
+            ("max_phase3_depth", self.max_phase3_depth),
+        ]
+        .into_iter()
+        .filter_map(|(name, bound)| Some((name, bound?)))
+    }
+}
+
+/// A self-describing record of a scramble which can be verified without any other information
+///
+/// The certificate contains the verification key, the cube index derived from it and the scramble
+/// generated from the index, so each step of generating the scramble can be checked on its own.
+/// The digest covers all other fields to detect accidental changes to the file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScrambleCertificate {
+    /// The version of Hypersolve which generated the scramble
+    pub version: String,
+    /// The verification key
+    pub key: String,
+    /// The tag the cube index is derived with
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub tag: String,
+    /// The name of the subset the scramble is generated from, empty for the full subset
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub subset: String,
+    /// The cube index derived from the key, written as a decimal string since it doesn't fit in
+    /// the integers of every format
+    pub cube_index: String,
+    /// The SHA256 hash of the other fields as a hexadecimal string
+    pub digest: String,
+    /// The scramble generated from the cube index
+    pub scramble: CertificateScramble,
+    /// The filter the scramble was chosen with, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<CertificateFilter>,
+}
+
+impl ScrambleCertificate {
+    /// Creates the certificate of the scramble generated from the key and tag in the subset
+    pub fn new(
+        key: &ScrambleKey,
+        tag: &str,
+        subset: ScrambleSubset,
+    ) -> Result<Self, ScrambleKeyError> {
+        let cube_index = key.to_subset_cube_index(tag, subset)?;
+        let scramble = new_scramble(cube_index);
+
+        let mut certificate = ScrambleCertificate {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            key: key.to_string(),
+            tag: tag.to_string(),
+            subset: match subset {
+                ScrambleSubset::Full => String::new(),
+                _ => subset.name().to_string(),
+            },
+            cube_index: cube_index.to_string(),
+            digest: String::new(),
+            scramble: CertificateScramble {
+                standard: Some(scramble.to_notation(Notation::Standard)),
+                mc4d: Some(scramble.to_notation(Notation::MC4D)),
+            },
+            filter: None,
+        };
+        certificate.digest = certificate.compute_digest();
+
+        Ok(certificate)
+    }
+
+    /// Creates the certificate of the scramble which the filter chose from the candidates of the
+    /// base key, after rejecting the given number of candidates
+    pub fn with_filter(
+        base_key: &ScrambleKey,
+        tag: &str,
+        subset: ScrambleSubset,
+        filter: &ScrambleFilter,
+        rejections: usize,
+    ) -> Result<Self, ScrambleKeyError> {
+        let key = ScrambleFilter::candidate_key(base_key, rejections);
+
+        let mut certificate = Self::new(&key, tag, subset)?;
+        certificate.filter = Some(CertificateFilter::new(base_key, rejections, filter));
+        certificate.digest = certificate.compute_digest();
+
+        Ok(certificate)
+    }
+
+    /// Computes the digest of all fields except the digest itself
+    pub fn compute_digest(&self) -> String {
+        let mut hasher = sha2::Sha256::new();
+
+        hasher.update("hypersolve scramble certificate\n");
+        hasher.update(format!("version: {}\n", self.version));
+        hasher.update(format!("key: {}\n", self.key));
+        if !self.tag.is_empty() {
+            hasher.update(format!("tag: {}\n", self.tag));
+        }
+        if !self.subset.is_empty() {
+            hasher.update(format!("subset: {}\n", self.subset));
+        }
+        hasher.update(format!("cube_index: {}\n", self.cube_index));
+        for (notation, scramble) in self.scramble.notations() {
+            hasher.update(format!("{notation}: {scramble}\n"));
+        }
+        if let Some(filter) = &self.filter {
+            hasher.update(format!("base_key: {}\n", filter.base_key));
+            hasher.update(format!("rejections: {}\n", filter.rejections));
+            for (name, bound) in filter.bounds() {
+                hasher.update(format!("{name}: {bound}\n"));
+            }
+        }
+
+        hex::encode(hasher.finalize())
+    }
+
+    /// Checks the digest, that the cube index is derived from the key and that each scramble is
+    /// the scramble generated from the cube index, returning the first check which fails
+    ///
+    /// MC4D notation has no double twists, so the scramble in each notation is compared to the
+    /// generated scramble written in the same notation.
+    ///
+    /// If the scramble was chosen with a filter, this also checks that the key is the candidate
+    /// after the rejected ones, that the scramble matches the filter and that none of the rejected
+    /// candidates match it. This can take a long time for filters on the optimal solution length.
+    pub fn verify(&self) -> Result<(), CertificateError> {
+        if self.scramble.notations().next().is_none() {
+            return Err(CertificateError::MissingScramble);
+        }
+
+        let scrambles = self
+            .scramble
+            .notations()
+            .map(|(notation, scramble)| {
+                notation
+                    .parse_twist_sequence(scramble)
+                    .map(|scramble| (notation, scramble))
+                    .map_err(|error| CertificateError::InvalidScramble { notation, error })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if !self.digest.eq_ignore_ascii_case(&self.compute_digest()) {
+            return Err(CertificateError::DigestMismatch);
+        }
+
+        let key = self
+            .key
+            .parse::<ScrambleKey>()
+            .map_err(CertificateError::InvalidKey)?;
+
+        let cube_index = self
+            .cube_index
+            .trim()
+            .parse::<u128>()
+            .ok()
+            .and_then(|index| CubeIndex::try_from(index).ok())
+            .ok_or_else(|| CertificateError::InvalidCubeIndex(self.cube_index.clone()))?;
+
+        let subset = match self.subset.as_str() {
+            "" => ScrambleSubset::Full,
+            name => ScrambleSubset::ALL
+                .into_iter()
+                .find(|subset| subset.name() == name)
+                .ok_or_else(|| CertificateError::InvalidSubset(name.to_string()))?,
+        };
+
+        let expected_index = key
+            .to_subset_cube_index(&self.tag, subset)
+            .map_err(CertificateError::InvalidKey)?;
+        if cube_index != expected_index {
+            return Err(CertificateError::IndexMismatch {
+                found: cube_index.to_string(),
+                expected: expected_index.to_string(),
+            });
+        }
+
+        let expected_scramble = new_scramble(cube_index);
+        for (notation, scramble) in scrambles {
+            let expected = notation
+                .parse_twist_sequence(&expected_scramble.to_notation(notation))
+                .expect("a formatted scramble should be valid");
+
+            if scramble != expected {
+                return Err(CertificateError::ScrambleMismatch(notation));
+            }
+        }
+
+        if let Some(record) = &self.filter {
+            let base_key = record
+                .base_key
+                .parse::<ScrambleKey>()
+                .map_err(CertificateError::InvalidKey)?;
+            if ScrambleFilter::candidate_key(&base_key, record.rejections) != key {
+                return Err(CertificateError::CandidateMismatch(record.rejections));
+            }
+
+            let filter = record.filter();
+            if !filter.matches(&Cube::SOLVED.twist_seq(expected_scramble)) {
+                return Err(CertificateError::FilterMismatch);
+            }
+
+            for candidate in 0..record.rejections {
+                let cube_index = ScrambleFilter::candidate_key(&base_key, candidate)
+                    .to_subset_cube_index(&self.tag, subset)
+                    .map_err(CertificateError::InvalidKey)?;
+
+                if filter.matches(&Cube::SOLVED.twist_seq(new_scramble(cube_index))) {
+                    return Err(CertificateError::RejectedMatch(candidate));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parses a certificate from TOML
+    pub fn from_toml_str(s: &str) -> Result<Self, CertificateError> {
+        toml::from_str(s).map_err(|err| CertificateError::InvalidFile(err.message().to_string()))
+    }
+
+    /// Parses a certificate from JSON
+    pub fn from_json_str(s: &str) -> Result<Self, CertificateError> {
+        serde_json::from_str(s).map_err(|err| CertificateError::InvalidFile(err.to_string()))
+    }
+
+    /// Formats the certificate as TOML
+    pub fn to_toml_string(&self) -> String {
+        toml::to_string(self).expect("a certificate should be serializable")
+    }
+
+    /// Formats the certificate as JSON
+    pub fn to_json_string(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a certificate should be serializable") + "\n"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: ScrambleKey = ScrambleKey::new(KeyVersion::V1, [3; 16]);
+
+    /// Returns a filter which rejects the scramble of the key and the first candidate after it
+    /// which the filter matches
+    fn filter_and_later_match() -> (ScrambleFilter, usize) {
+        let cube = Cube::SOLVED.twist_seq(new_scramble(KEY.to_cube_index("").unwrap()));
+
+        let mut filter = ScrambleFilter::default();
+        filter.max_phase_depths[0] = Some(cube.phase_depths()[0] - 1);
+
+        let first = filter.find(&KEY, "", ScrambleSubset::Full, 1000).unwrap();
+        let later = (first.rejections + 1..)
+            .find(|&candidate| {
+                let key = ScrambleFilter::candidate_key(&KEY, candidate);
+                let scramble = new_scramble(key.to_cube_index("").unwrap());
+                filter.matches(&Cube::SOLVED.twist_seq(scramble))
+            })
+            .unwrap();
+
+        (filter, later)
+    }
+
+    #[test]
+    fn test_filtered_certificate() {
+        let (filter, _) = filter_and_later_match();
+        let found = filter.find(&KEY, "", ScrambleSubset::Full, 1000).unwrap();
+
+        let certificate = ScrambleCertificate::with_filter(
+            &KEY,
+            "",
+            ScrambleSubset::Full,
+            &filter,
+            found.rejections,
+        )
+        .unwrap();
+        assert_eq!(certificate.key, found.key.to_string());
+        assert!(certificate.verify().is_ok());
+
+        let toml = ScrambleCertificate::from_toml_str(&certificate.to_toml_string()).unwrap();
+        assert_eq!(toml, certificate);
+        let json = ScrambleCertificate::from_json_str(&certificate.to_json_string()).unwrap();
+        assert_eq!(json, certificate);
+
+        // the filter is covered by the digest
+        let mut changed = certificate.clone();
+        changed.filter.as_mut().unwrap().max_phase1_depth = None;
+        assert!(matches!(
+            changed.verify(),
+            Err(CertificateError::DigestMismatch)
+        ));
+
+        // the key has to be the candidate after the rejected ones
+        let mut changed = certificate.clone();
+        changed.filter.as_mut().unwrap().rejections += 1;
+        changed.digest = changed.compute_digest();
+        assert!(matches!(
+            changed.verify(),
+            Err(CertificateError::CandidateMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_picked_candidate() {
+        let (filter, later) = filter_and_later_match();
+        let first = filter.find(&KEY, "", ScrambleSubset::Full, 1000).unwrap();
+
+        // a later candidate which matches the filter can't be passed off as the first match
+        let certificate =
+            ScrambleCertificate::with_filter(&KEY, "", ScrambleSubset::Full, &filter, later)
+                .unwrap();
+        assert!(matches!(
+            certificate.verify(),
+            Err(CertificateError::RejectedMatch(candidate)) if candidate == first.rejections
+        ));
+
+        // and a candidate which doesn't match the filter can't be passed off as a match
+        let certificate =
+            ScrambleCertificate::with_filter(&KEY, "", ScrambleSubset::Full, &filter, 0).unwrap();
+        assert!(matches!(
+            certificate.verify(),
+            Err(CertificateError::FilterMismatch)
+        ));
+    }
+}
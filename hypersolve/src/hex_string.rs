@@ -1,11 +1,8 @@
-use sha2::Digest;
 use std::{
     fmt::{Debug, Display},
     str::FromStr,
 };
 
-use super::*;
-
 #[derive(Debug, Clone, Copy, thiserror::Error)]
 pub enum HexStringError<const N: usize> {
     #[error("invalid character '{c}' at position {index}, valid characters are: 0...9, a...f")]
@@ -50,21 +47,6 @@ impl<const N: usize> HexString<N> {
         getrandom::getrandom(&mut bytes).expect("unable to generate random key");
         Self::from_bytes(bytes)
     }
-
-    pub fn hash(&self) -> [u8; 32] {
-        let mut hasher = sha2::Sha256::new();
-
-        hasher.update(self.bytes());
-
-        hasher.finalize().into()
-    }
-
-    pub fn to_cube_index(&self) -> CubeIndex {
-        CubeIndex::try_from(
-            u128::from_le_bytes(self.hash()[..16].try_into().unwrap()) % N_CUBE_STATES,
-        )
-        .unwrap()
-    }
 }
 
 impl<const N: usize> FromStr for HexString<N> {
@@ -1,6 +1,16 @@
+mod certificate;
 mod hex_string;
+mod net;
+mod scramble_set;
+mod solve_checkpoint;
+mod study_file;
 mod util;
 
+pub use certificate::*;
 pub use hex_string::*;
 pub use hypersolve_lib::*;
+pub use net::*;
+pub use scramble_set::*;
+pub use solve_checkpoint::*;
+pub use study_file::*;
 pub use util::*;
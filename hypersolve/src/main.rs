@@ -1,4 +1,7 @@
+use std::collections::HashSet;
 use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::time::Duration;
 
 use clap::{Parser, Subcommand, ValueEnum};
 use colored::Colorize;
@@ -23,16 +26,20 @@ enum Commands {
     /// Finds relatively short solutions very quickly by iteratively searching for solutions shorter than the last one
     FastSolve {
         /// Scramble moves
-        moves: Vec<Twist>,
+        moves: Vec<String>,
 
         /// The notation in which to output the solution
         #[arg(short, long, value_enum, default_value_t = NotationEnum::Standard)]
         output: NotationEnum,
+
+        /// Also output solutions with the same length as the last one which are easier to do by the cost model
+        #[arg(short, long, value_enum)]
+        cost: Option<CostPresetEnum>,
     },
     /// Finds the shortest possible solutions in order of increasing length
     OptimalSolve {
         /// Scramble moves
-        moves: Vec<Twist>,
+        moves: Vec<String>,
 
         /// Minimum solution length
         #[arg(short, long, default_value_t = 0)]
@@ -53,48 +60,379 @@ enum Commands {
         /// The notation in which to output the solution
         #[arg(short, long, value_enum, default_value_t = NotationEnum::Standard)]
         output: NotationEnum,
+
+        /// Periodically save the search to the file and resume from it if it exists
+        #[arg(short, long)]
+        checkpoint: Option<PathBuf>,
+
+        /// The time between saving the search to the checkpoint file in seconds
+        #[arg(long, default_value_t = 60.0, requires = "checkpoint")]
+        checkpoint_interval: f64,
+
+        /// Search the solutions of each length on every thread
+        #[arg(long, conflicts_with = "checkpoint")]
+        parallel: bool,
+
+        /// Output the solutions of each length in the same order as a search without --parallel
+        #[arg(long, requires = "parallel")]
+        deterministic: bool,
+
+        /// Only output one solution of each set of equivalent solutions, with its commuting twists in a canonical order
+        #[arg(short, long, value_enum, conflicts_with = "checkpoint")]
+        distinct: Option<EquivalenceEnum>,
+
+        /// Count the optimal solutions and output one solution of each set of equivalent solutions (equivalent up to commutation unless --distinct is given)
+        #[arg(long, conflicts_with_all = ["number", "per_length", "checkpoint"])]
+        count: bool,
+
+        /// Output the solutions of each length from the easiest to the hardest to do by the cost model, which waits for every solution of the length to be found
+        #[arg(short, long, value_enum, conflicts_with = "checkpoint")]
+        rank: Option<CostPresetEnum>,
+    },
+    /// Finds the shortest move sequences which take the state after the moves to the state after other moves, up to whole cube rotation
+    Path {
+        /// Moves giving the starting state
+        moves: Vec<String>,
+
+        /// Moves giving the target state
+        #[arg(short, long, num_args = 1.., required = true)]
+        to: Vec<String>,
+
+        /// Find relatively short sequences quickly like fast-solve instead of the shortest sequences
+        #[arg(short, long)]
+        fast: bool,
+
+        /// Limit the number of sequences
+        #[arg(short, long, default_value_t = 1, conflicts_with = "fast")]
+        number: usize,
+
+        /// Limit the length of sequences
+        #[arg(short('l'), long)]
+        max: Option<usize>,
+
+        /// The notation in which to output the sequences
+        #[arg(short, long, value_enum, default_value_t = NotationEnum::Standard)]
+        output: NotationEnum,
+    },
+    /// Finds the shortest move sequences which take the state after the moves to a partial goal, such as solving some pieces and orienting others
+    AlgSearch {
+        /// Moves giving the case to search from
+        moves: Vec<String>,
+
+        /// The goal as comma separated <pieces>:<goal> entries, where the pieces are * or the faces the pieces are on (e.g. I or RUFI) and the goal is ignore, orientation, permutation or full. Later entries override earlier ones and pieces which aren't given are ignored.
+        #[arg(short, long)]
+        goal: GoalMask,
+
+        /// Limit the number of sequences
+        #[arg(short, long, default_value_t = 1)]
+        number: usize,
+
+        /// Minimum sequence length
+        #[arg(short, long, default_value_t = 0)]
+        min: usize,
+
+        /// Limit the length of sequences
+        #[arg(short('l'), long)]
+        max: Option<usize>,
+
+        /// The notation in which to output the sequences
+        #[arg(short, long, value_enum, default_value_t = NotationEnum::Standard)]
+        output: NotationEnum,
     },
     /// Compute bounds on the length of the optimal solution to a scramble
     Bound {
         /// Scramble moves
-        moves: Vec<Twist>,
+        moves: Vec<String>,
+    },
+    /// Counts the states of a solving phase at each distance from its goal by searching every state (phase 1 takes minutes and phase 2 takes hours and about 5 GB of memory)
+    Stats {
+        /// The phase to search
+        #[arg(value_enum)]
+        phase: PhaseArgEnum,
+
+        /// The notation in which to output scrambles of the states at the largest distance
+        #[arg(short, long, value_enum, default_value_t = NotationEnum::Standard)]
+        output: NotationEnum,
+    },
+    /// Estimates the distribution of optimal solution lengths by searching random states, writing the results to a file which the study resumes from if it is run again
+    Study {
+        /// The results file
+        file: PathBuf,
+
+        /// The total number of states to sample
+        #[arg(short, long, default_value_t = 100)]
+        number: usize,
+
+        /// The time limit for searching each state in seconds
+        #[arg(short, long, default_value_t = 60.0)]
+        time_limit: f64,
+
+        /// How to search each state (optimal by default, must match the file when resuming)
+        #[arg(short, long, value_enum)]
+        mode: Option<StudyModeEnum>,
+
+        /// Use a custom 128 bit hexadecimal key to derive the states from instead of generating a random key (must match the file when resuming)
+        #[arg(short, long)]
+        key: Option<ScrambleKey>,
     },
     /// Generates a true random state scramble
     Scramble {
-        /// Use a custom 128 bit hexadecimal verification key (32 hexadecimal characters) instead of generating a random key
-        /// Use a custom 128 bit hexadecimal verification key (32 hexadecimal characters) instead of generating a random key
+        /// Use a custom 128 bit hexadecimal verification key (32 hexadecimal characters, optionally prefixed by a 2 character version) instead of generating a random key
+        /// Use a custom 128 bit hexadecimal verification key (32 hexadecimal characters, optionally prefixed by a 2 character version) instead of generating a random key
         #[arg(short, long)]
-        key: Option<HexString<16>>,
+        key: Option<ScrambleKey>,
+
+        /// Tag such as the name of an event which the scramble is derived with
+        #[arg(short, long, default_value = "")]
+        tag: String,
 
         /// The notation in which to output the scramble
         #[arg(short, long, value_enum, default_value_t = NotationEnum::Standard)]
         output: NotationEnum,
+
+        /// Also write a certificate of the scramble to the file (TOML if the file ends in .toml, JSON otherwise)
+        #[arg(short, long)]
+        certificate: Option<PathBuf>,
+
+        /// Only generate scrambles from a subset of the states
+        #[arg(short, long, value_enum, default_value_t = SubsetEnum::Full)]
+        subset: SubsetEnum,
+
+        /// Only accept scrambles with an optimal solution of at least this length (can be slow)
+        #[arg(long)]
+        min_length: Option<usize>,
+
+        /// Only accept scrambles with an optimal solution of at most this length (can be slow)
+        #[arg(long)]
+        max_length: Option<usize>,
+
+        /// Only accept scrambles with at least this phase 1 depth
+        #[arg(long)]
+        min_phase1_depth: Option<usize>,
+
+        /// Only accept scrambles with at most this phase 1 depth
+        #[arg(long)]
+        max_phase1_depth: Option<usize>,
+
+        /// Only accept scrambles with at least this phase 2 depth
+        #[arg(long)]
+        min_phase2_depth: Option<usize>,
+
+        /// Only accept scrambles with at most this phase 2 depth
+        #[arg(long)]
+        max_phase2_depth: Option<usize>,
+
+        /// Only accept scrambles with at least this phase 3 depth
+        #[arg(long)]
+        min_phase3_depth: Option<usize>,
+
+        /// Only accept scrambles with at most this phase 3 depth
+        #[arg(long)]
+        max_phase3_depth: Option<usize>,
+
+        /// Maximum number of candidate keys to try before giving up
+        #[arg(long, default_value_t = 10000)]
+        max_candidates: usize,
+    },
+    /// Generates a reproducible set of true random state scrambles for a competition
+    ScrambleSet {
+        /// Use a custom 128 bit hexadecimal master key (32 hexadecimal characters, optionally prefixed by a 2 character version) instead of generating a random key
+        #[arg(short, long)]
+        key: Option<ScrambleKey>,
+
+        /// Tag such as the name of an event which the scrambles are derived with
+        #[arg(short, long, default_value = "")]
+        tag: String,
+
+        /// Number of rounds
+        #[arg(short, long, default_value_t = 1)]
+        rounds: usize,
+
+        /// Number of scrambles per round
+        #[arg(short, long, default_value_t = 5)]
+        scrambles: usize,
+
+        /// Number of extra scrambles per round
+        #[arg(short, long, default_value_t = 2)]
+        extras: usize,
+
+        /// The format of the scramble set
+        #[arg(short, long, value_enum, default_value_t = FormatEnum::Text)]
+        format: FormatEnum,
+
+        /// The notation in which to output the scrambles
+        #[arg(short, long, value_enum, default_value_t = NotationEnum::Standard)]
+        output: NotationEnum,
+
+        /// File to write the scramble set to instead of printing it
+        #[arg(long)]
+        file: Option<PathBuf>,
     },
     /// Verifies that a scramble was randomly generated by Hypersolve
     VerifyScramble {
         /// 128 bit hexadecimal verification key
-        key: HexString<16>,
+        #[arg(required_unless_present = "certificate")]
+        key: Option<ScrambleKey>,
 
         /// Scramble moves
         /// Scramble moves
-        scramble: Vec<Twist>       
+        scramble: Vec<String>,
+
+        /// Tag which the scramble was derived with
+        #[arg(short, long, default_value = "", conflicts_with = "certificate")]
+        tag: String,
+
+        /// The subset which the scramble was generated from
+        #[arg(long, value_enum, default_value_t = SubsetEnum::Full, conflicts_with = "certificate")]
+        subset: SubsetEnum,
+
+        /// How the scramble is compared to the scramble generated from the key
+        #[arg(short, long, value_enum, default_value_t = VerifyModeEnum::Strict)]
+        mode: VerifyModeEnum,
+
+        /// Verify a scramble certificate file instead of a key and scramble
+        #[arg(short, long, conflicts_with_all = ["key", "scramble", "mode"])]
+        certificate: Option<PathBuf>,
     },
     /// Computes the inverse of a move sequence
     Invert {
         /// Moves to invert
-        moves: Vec<Twist>,
+        moves: Vec<String>,
 
         /// The notation in which to output the result
         #[arg(short, long, value_enum, default_value_t = NotationEnum::Standard)]
         output: NotationEnum,
     },
-    /// Converts the moves to the given notation
+    /// Converts the moves or a log file to the given notation
     Convert {
-        /// Moves to invert
-        moves: Vec<Twist>,
+        /// Moves to convert, optionally followed by the notation in which to output the result and a log file to write it to
+        moves: Vec<String>,
+
+        /// The notation in which to output the result (standard if not given)
+        #[arg(short, long, value_enum)]
+        output: Option<NotationEnum>,
+
+        /// The notation of the moves (detected automatically if not given)
+        #[arg(short, long, value_enum)]
+        input: Option<NotationEnum>,
+
+        /// Read the scramble and solution from an MC4D or Hyperspeedcube log file instead of the moves
+        #[arg(short, long)]
+        log: Option<PathBuf>,
+
+        /// Write the result to an MC4D or Hyperspeedcube log file (depending on the output notation).
+        /// Moves are written as the scramble.
+        #[arg(short, long)]
+        file: Option<PathBuf>,
+
+        /// Keep the groups, commutators, conjugates and comments of the moves instead of expanding them
+        #[arg(short, long, conflicts_with_all = ["log", "file"])]
+        keep_structure: bool,
+    },
+    /// Removes redundant moves by cancelling, merging and reordering commuting moves
+    Simplify {
+        /// Moves to simplify
+        moves: Vec<String>,
+
+        /// How to treat whole cube rotations
+        #[arg(short, long, value_enum, default_value_t = RotationEnum::Keep)]
+        rotations: RotationEnum,
+
         /// The notation in which to output the result
+        #[arg(short, long, value_enum, default_value_t = NotationEnum::Standard)]
         output: NotationEnum,
     },
+    /// Computes the mirror image of a move sequence
+    Mirror {
+        /// Moves to mirror
+        moves: Vec<String>,
+
+        /// The axis to mirror across
+        #[arg(short, long, value_enum, default_value_t = AxisEnum::X)]
+        axis: AxisEnum,
+
+        /// The notation in which to output the result
+        #[arg(short, long, value_enum, default_value_t = NotationEnum::Standard)]
+        output: NotationEnum,
+    },
+    /// Shows the order and piece cycles of a move sequence
+    Analyze {
+        /// Moves to analyze
+        moves: Vec<String>,
+
+        /// Other moves to compare the effect of the moves with
+        #[arg(short, long)]
+        compare: Option<String>,
+    },
+    /// Shows the state of the cube after applying the moves
+    Show {
+        /// Moves to apply to a solved cube
+        moves: Vec<String>,
+
+        /// Color scheme preset (mc4d or hyperspeedcube) or TOML/JSON color scheme file. Facelets are
+        /// written using the color symbols of the scheme if it is given.
+        #[arg(short, long, value_parser = parse_color_scheme)]
+        colors: Option<ColorScheme>,
+    },
+    /// Renders the state of the cube after applying the moves as an SVG image
+    Render {
+        /// Moves to apply to a solved cube
+        moves: Vec<String>,
+
+        /// File to write the image to instead of printing it
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// How to arrange the cells of the cube
+        #[arg(short, long, value_enum, default_value_t = ViewEnum::Net)]
+        view: ViewEnum,
+
+        /// Pieces to highlight given by the faces of their solved location (e.g. RUFI)
+        #[arg(long, value_delimiter = ',')]
+        highlight: Vec<PieceLocation>,
+
+        /// Render the cube after each move from left to right instead of only the final state
+        #[arg(short, long)]
+        strip: bool,
+
+        /// Size of a sticker in pixels
+        #[arg(long, default_value_t = 20.0)]
+        sticker_size: f64,
+
+        /// Color scheme preset (mc4d or hyperspeedcube) or TOML/JSON color scheme file
+        #[arg(short, long, value_parser = parse_color_scheme, default_value = "hyperspeedcube")]
+        colors: ColorScheme,
+
+        /// The notation in which to label the moves of a strip
+        #[arg(short, long, value_enum, default_value_t = NotationEnum::Standard)]
+        notation: NotationEnum,
+    },
+    /// Reads the scramble and solution from an MC4D or Hyperspeedcube log file
+    ImportLog {
+        /// MC4D or Hyperspeedcube log file
+        file: PathBuf,
+
+        /// The notation in which to output the moves
+        #[arg(short, long, value_enum, default_value_t = NotationEnum::Standard)]
+        output: NotationEnum,
+    },
+    /// Writes a scramble and solution as an MC4D log file
+    ExportLog {
+        /// Scramble moves
+        moves: Vec<String>,
+
+        /// Moves made after the scramble
+        #[arg(long, num_args = 1.., conflicts_with = "solve")]
+        solution: Vec<String>,
+
+        /// Solve the scramble with Hypersolve and write the solution after the scramble
+        #[arg(long)]
+        solve: bool,
+
+        /// File to write the log to instead of printing it
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
@@ -102,7 +440,9 @@ enum NotationEnum {
     /// Standard twist notation as described in https://hypercubing.xyz/notation
     Standard,
     /// Twist notation used in MC4D
-    MC4D
+    MC4D,
+    /// Twist notation used in Hyperspeedcube
+    Hyperspeedcube,
 }
 
 
@@ -110,7 +450,187 @@ impl From<NotationEnum> for Notation {
     fn from(value: NotationEnum) -> Self {
         match value {
             NotationEnum::Standard => Notation::Standard,
-            NotationEnum::MC4D => Notation::MC4D
+            NotationEnum::MC4D => Notation::MC4D,
+            NotationEnum::Hyperspeedcube => Notation::Hyperspeedcube,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum FormatEnum {
+    /// Human readable text
+    Text,
+    /// JSON
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum VerifyModeEnum {
+    /// The scramble must be exactly the generated scramble
+    Strict,
+    /// The scramble must result in the same state as the generated scramble
+    State,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum RotationEnum {
+    /// Keep rotations where they are
+    Keep,
+    /// Move rotations to the end by rewriting the moves after them
+    Push,
+    /// Move rotations to the end and remove them
+    Remove,
+}
+
+impl From<RotationEnum> for RotationMode {
+    fn from(value: RotationEnum) -> Self {
+        match value {
+            RotationEnum::Keep => RotationMode::Keep,
+            RotationEnum::Push => RotationMode::PushToEnd,
+            RotationEnum::Remove => RotationMode::Remove,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum AxisEnum {
+    /// Swap the R and L faces
+    X,
+    /// Swap the U and D faces
+    Y,
+    /// Swap the F and B faces
+    Z,
+    /// Swap the O and I faces
+    W,
+}
+
+impl From<AxisEnum> for Axis {
+    fn from(value: AxisEnum) -> Self {
+        match value {
+            AxisEnum::X => Axis::X,
+            AxisEnum::Y => Axis::Y,
+            AxisEnum::Z => Axis::Z,
+            AxisEnum::W => Axis::W,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum PhaseArgEnum {
+    /// Orients the pieces up to K4 orientation
+    Phase1,
+    /// Separates the I and O pieces and finishes orienting the pieces
+    Phase2,
+    /// Permutes the pieces
+    Phase3,
+}
+
+impl From<PhaseArgEnum> for PhaseEnum {
+    fn from(value: PhaseArgEnum) -> Self {
+        match value {
+            PhaseArgEnum::Phase1 => PhaseEnum::Phase1,
+            PhaseArgEnum::Phase2 => PhaseEnum::Phase2,
+            PhaseArgEnum::Phase3 => PhaseEnum::Phase3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum StudyModeEnum {
+    /// Finds increasingly shorter solutions to improve the upper bound
+    Bounds,
+    /// Searches each solution length in turn to find the optimal solution length
+    Optimal,
+}
+
+impl From<StudyModeEnum> for StudyMode {
+    fn from(value: StudyModeEnum) -> Self {
+        match value {
+            StudyModeEnum::Bounds => StudyMode::Bounds,
+            StudyModeEnum::Optimal => StudyMode::Optimal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum EquivalenceEnum {
+    /// Solutions which only differ by the order of commuting twists
+    Commutation,
+    /// Solutions which differ by the order of commuting twists and a whole cube rotation
+    Rotation,
+    /// Solutions which differ by the order of commuting twists and a rotation or mirror image of the whole cube
+    Symmetry,
+}
+
+impl From<EquivalenceEnum> for SequenceEquivalence {
+    fn from(value: EquivalenceEnum) -> Self {
+        match value {
+            EquivalenceEnum::Commutation => SequenceEquivalence::Commutation,
+            EquivalenceEnum::Rotation => SequenceEquivalence::Rotation,
+            EquivalenceEnum::Symmetry => SequenceEquivalence::Symmetry,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CostPresetEnum {
+    /// Prefers 90-degree twists and avoids I and O twists, gyros and changes of axis
+    Physical,
+    /// Prefers 90-degree twists
+    Virtual,
+}
+
+impl From<CostPresetEnum> for CostModel {
+    fn from(value: CostPresetEnum) -> Self {
+        match value {
+            CostPresetEnum::Physical => CostModel::PHYSICAL,
+            CostPresetEnum::Virtual => CostModel::VIRTUAL,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum SubsetEnum {
+    /// All states
+    Full,
+    /// Only the orientation of the pieces scrambled
+    Orientation,
+    /// Only the permutation of the pieces scrambled
+    Permutation,
+    /// States with phase 1 solved
+    Phase2,
+    /// States with phases 1 and 2 solved
+    Phase3,
+    /// Only the pieces of the O cell unsolved
+    LastCell,
+}
+
+impl From<SubsetEnum> for ScrambleSubset {
+    fn from(value: SubsetEnum) -> Self {
+        match value {
+            SubsetEnum::Full => ScrambleSubset::Full,
+            SubsetEnum::Orientation => ScrambleSubset::Orientation,
+            SubsetEnum::Permutation => ScrambleSubset::Permutation,
+            SubsetEnum::Phase2 => ScrambleSubset::Phase2,
+            SubsetEnum::Phase3 => ScrambleSubset::Phase3,
+            SubsetEnum::LastCell => ScrambleSubset::LastCell,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ViewEnum {
+    /// A flat net of the eight cells
+    Net,
+    /// A 3D projection of the cells like MC4D
+    Projected,
+}
+
+impl From<ViewEnum> for RenderView {
+    fn from(value: ViewEnum) -> Self {
+        match value {
+            ViewEnum::Net => RenderView::Net,
+            ViewEnum::Projected => RenderView::Projected,
         }
     }
 }
@@ -129,65 +649,238 @@ fn main() {
     let args = Cli::parse();
 
     match args.command {
-        Commands::FastSolve { moves ,  output} => {
-            let cube = Cube::SOLVED.twist_seq(moves);
-    
-            let solutions = cube.fast_solutions(None);
-                
-            for (solution, length) in solutions {
-                println!("[{}]", format!("{} STM", length).yellow().underline());
+        Commands::FastSolve { moves ,  output, cost} => {
+            let cube = Cube::SOLVED.twist_seq(parse_moves(&moves));
+
+            let Some(cost) = cost.map(CostModel::from) else {
+                for (solution, length) in cube.fast_solutions(None) {
+                    println!("[{}]", format!("{} STM", length).yellow().underline());
+                    println!("{}", solution.to_notation(output.into()));
+                }
+                return;
+            };
+
+            for (solution, length) in cube.fast_solutions_by_cost(None, cost) {
+                println!("[{}]", format!("{} STM, cost {}", length, cost.cost(&solution)).yellow().underline());
                 println!("{}", solution.to_notation(output.into()));
             }
         },
-        Commands::OptimalSolve { moves , min, max, number, per_length, output} =>  {
-            fn print_solutions(mut solutions: ShortestSolutionIterator, notation: NotationEnum, number: Option<usize>,  per_length: Option<NonZeroUsize>) {               
-                let mut sol_len = None;
-                let mut count = number.map(|n| (0_usize, n));
-                let mut per_length_count = per_length.map(|p| (0_usize, p));
+        Commands::OptimalSolve { moves , min, max, number, per_length, output, checkpoint, checkpoint_interval, parallel, deterministic, distinct, count, rank} =>  {
+            fn print_solutions(mut solutions: impl SolutionSearch, notation: NotationEnum, number: Option<usize>,  per_length: Option<NonZeroUsize>, distinct: Option<SequenceEquivalence>, mut progress: SolveProgress, mut checkpoint: Option<CheckpointWriter>) {
+                // the canonical forms of the solutions printed with the current length
+                let mut printed = HashSet::new();
 
-                while let Some(solution) = solutions.next() {
-                    if count.is_some_and(|(c, l)| c >= l){
-                        return;
-                    }
-                    if per_length_count.is_some_and(|(c, l)| c >= usize::from(l)) {
-                        per_length_count = Some((0,per_length_count.unwrap().1));
-                        solutions.set_to_length(sol_len.unwrap_or(0) + 1);
+                while number.is_none_or(|n| progress.solutions < n) {
+                    let solution = match solutions.next_before(checkpoint.as_ref().map(|checkpoint| checkpoint.deadline())) {
+                        Ok(Some(solution)) => solution,
+                        Ok(None) => break,
+                        Err(TimedOut) => {
+                            checkpoint.as_mut().unwrap().write(&solutions.checkpoint().expect("a search with a checkpoint file should have a checkpoint"), progress);
+                            continue;
+                        }
+                    };
+
+                    if per_length.is_some_and(|p| progress.solutions_at_length >= usize::from(p)) {
+                        progress.solutions_at_length = 0;
+                        solutions.set_to_length(progress.length.unwrap_or(0) + 1);
                         continue;
                     }
 
-                    if sol_len != Some(solution.len()) {
-                        if let Some((c, _)) = per_length_count.as_mut() {
-                            *c = 0;
+                    let solution = match distinct {
+                        None => solution,
+                        Some(equivalence) => {
+                            // solutions with different lengths are never equivalent
+                            if progress.length != Some(solution.len()) {
+                                printed.clear();
+                            }
+                            if !printed.insert(solution.canonical_form(equivalence)) {
+                                continue;
+                            }
+                            solution.sort_commuting()
                         }
+                    };
 
-                        sol_len = Some(solution.len());
-                        if let Some((c, _)) = per_length_count.as_mut() {
-                            *c = 0;
-                        }
+                    if progress.length != Some(solution.len()) {
+                        progress.length = Some(solution.len());
+                        progress.solutions_at_length = 0;
 
                         println!("[{}]", format!("{} STM", solution.len()).yellow().underline());
                     }
 
                     println!("{}", Notation::from(notation).format_twist_sequence(&solution));
-                    if let Some((c, _)) = per_length_count.as_mut() {
-                        *c += 1;
+                    progress.solutions_at_length += 1;
+                    progress.solutions += 1;
+
+                    if let (Some(checkpoint), Some(position)) = (checkpoint.as_mut(), solutions.checkpoint()) {
+                        checkpoint.write(&position, progress);
                     }
-                    if let Some((c, _)) = count.as_mut() {
-                        *c += 1;
+                }
+
+                // the search is finished or printed every solution asked for, so there is nothing left
+                // to resume
+                if let Some(checkpoint) = checkpoint {
+                    checkpoint.finish();
+                }
+            }
+
+            fn print_solution_count(solutions: impl Iterator<Item = TwistSequence>, notation: NotationEnum, equivalence: SequenceEquivalence) {
+                let mut distinct = HashSet::new();
+                let mut total = 0;
+                let mut length = None;
+
+                for solution in solutions {
+                    if length.is_none() {
+                        length = Some(solution.len());
+                        println!("[{}]", format!("{} STM", solution.len()).yellow().underline());
+                    }
+
+                    total += 1;
+                    if distinct.insert(solution.canonical_form(equivalence)) {
+                        println!("{}", Notation::from(notation).format_twist_sequence(&solution.sort_commuting()));
                     }
                 }
 
-            }            
-            
-            let cube = Cube::SOLVED.twist_seq(moves);
-            
-            match max {
-                None =>  print_solutions(cube.solutions(min..), output,  number, per_length),
-                Some(len) => print_solutions(cube.solutions(min..=len), output,   number, per_length),
+                if let Some(length) = length {
+                    println!();
+                    println!("Solutions of length {length}: {total}");
+                    println!("Distinct up to {}: {}", equivalence.name(), distinct.len());
+                }
+            }
+
+            let scramble = parse_moves(&moves);
+            let lengths = (std::ops::Bound::Included(min), max.map_or(std::ops::Bound::Unbounded, std::ops::Bound::Included));
+
+            if count {
+                let cube = Cube::SOLVED.twist_seq(scramble);
+                let equivalence = distinct.map_or(SequenceEquivalence::Commutation, SequenceEquivalence::from);
+
+                // the first solution gives the optimal length, so the search stops after that length
+                let (first, solutions): (_, Box<dyn Iterator<Item = TwistSequence>>) = match parallel {
+                    false => {
+                        let mut solutions = cube.solutions(lengths);
+                        let first = solutions.next();
+                        if let Some(first) = &first {
+                            solutions.limit_length(first.len());
+                        }
+                        (first, Box::new(solutions))
+                    }
+                    true => {
+                        let mut solutions = cube.parallel_solutions(lengths, deterministic);
+                        let first = solutions.next();
+                        if let Some(first) = &first {
+                            solutions.limit_length(first.len());
+                        }
+                        (first, Box::new(solutions))
+                    }
+                };
+                let Some(first) = first else {
+                    exit_with_error("No solutions within the length limit");
+                };
+                let solutions = std::iter::once(first).chain(solutions);
+
+                // ranking the solutions makes the cheapest solution of each set the one output
+                match rank {
+                    None => print_solution_count(solutions, output, equivalence),
+                    Some(cost) => print_solution_count(RankedSolutionIterator::new(solutions, cost.into()), output, equivalence),
+                }
+                return;
+            }
+
+            let distinct = distinct.map(SequenceEquivalence::from);
+
+            let Some(path) = checkpoint else {
+                let cube = Cube::SOLVED.twist_seq(scramble);
+
+                let solutions: Box<dyn SolutionSearch> = match (parallel, rank.map(CostModel::from)) {
+                    (false, None) => Box::new(cube.solutions(lengths)),
+                    (true, None) => Box::new(cube.parallel_solutions(lengths, deterministic)),
+                    (false, Some(cost)) => Box::new(RankedSolutionIterator::new(cube.solutions(lengths), cost)),
+                    (true, Some(cost)) => Box::new(RankedSolutionIterator::new(cube.parallel_solutions(lengths, deterministic), cost)),
+                };
+
+                print_solutions(solutions, output, number, per_length, distinct, SolveProgress::default(), None);
+                return;
+            };
+
+            let interval = Duration::try_from_secs_f64(checkpoint_interval).ok().filter(|interval| !interval.is_zero()).unwrap_or_else(|| exit_with_error("The checkpoint interval must be a positive number of seconds"));
+
+            if path.exists() {
+                let saved = SolveCheckpoint::from_json_str(&read_input(&path)).unwrap_or_else(|err| exit_with_error(err));
+                let saved_scramble = saved.scramble().unwrap_or_else(|err| exit_with_error(err));
+
+                if !moves.is_empty() && !Cube::SOLVED.twist_seq(scramble).is_equivalent(&Cube::SOLVED.twist_seq(saved_scramble.clone()), false) {
+                    exit_with_error(format!("The scramble does not match the scramble {} of the checkpoint", saved.scramble));
+                }
+
+                // the search continues with the limits it was started with
+                if max.is_some_and(|max| saved.length_limit != Some(max + 1)) {
+                    let saved_max = saved.length_limit.map_or("no limit".to_string(), |limit| (limit - 1).to_string());
+                    exit_with_error(format!("The maximum length does not match the maximum length of the checkpoint ({saved_max})"));
+                }
+                if min > saved.solution_length {
+                    exit_with_error(format!("The minimum length is more than the length {} which the checkpoint is searching", saved.solution_length));
+                }
+
+                let solutions = saved.resume().unwrap_or_else(|err| exit_with_error(err));
+
+                println!("Resuming the search at length {} after {} solutions", saved.solution_length, saved.progress.solutions);
+                print_solutions(solutions, output, number, per_length, None, saved.progress, Some(CheckpointWriter::new(&path, saved_scramble, interval)));
+            } else {
+                let cube = Cube::SOLVED.twist_seq(scramble.clone());
+                let checkpoint = CheckpointWriter::new(&path, scramble, interval);
+
+                match max {
+                    None =>  print_solutions(cube.solutions(min..), output,  number, per_length, None, SolveProgress::default(), Some(checkpoint)),
+                    Some(len) => print_solutions(cube.solutions(min..=len), output,   number, per_length, None, SolveProgress::default(), Some(checkpoint)),
+                }
+            }
+        },
+        Commands::Path { moves, to, fast, number, max, output } => {
+            let start = Cube::SOLVED.twist_seq(parse_moves(&moves));
+            let target = Cube::SOLVED.twist_seq(parse_moves(&to));
+
+            if fast {
+                for (path, length) in start.fast_solutions_to(&target, max) {
+                    println!("[{}]", format!("{} STM", length).yellow().underline());
+                    println!("{}", path.to_notation(output.into()));
+                }
+                return;
+            }
+
+            let lengths = (std::ops::Bound::Included(0), max.map_or(std::ops::Bound::Unbounded, std::ops::Bound::Included));
+            let mut length = None;
+
+            for path in start.solutions_to(&target, lengths).take(number) {
+                if length != Some(path.len()) {
+                    length = Some(path.len());
+                    println!("[{}]", format!("{} STM", path.len()).yellow().underline());
+                }
+                println!("{}", path.to_notation(output.into()));
+            }
+
+            if length.is_none() {
+                exit_with_error("No sequences within the length limit");
+            }
+        },
+        Commands::AlgSearch { moves, goal, number, min, max, output } => {
+            let cube = Cube::SOLVED.twist_seq(parse_moves(&moves));
+            let lengths = (std::ops::Bound::Included(min), max.map_or(std::ops::Bound::Unbounded, std::ops::Bound::Included));
+            let mut length = None;
+
+            for alg in cube.alg_search(&goal, lengths).take(number) {
+                if length != Some(alg.len()) {
+                    length = Some(alg.len());
+                    println!("[{}]", format!("{} STM", alg.len()).yellow().underline());
+                }
+                println!("{}", alg.to_notation(output.into()));
+            }
+
+            if length.is_none() {
+                exit_with_error("No sequences within the length limit");
             }
         },
         Commands::Bound { moves } => {
-            let bounds = Cube::SOLVED.twist_seq(moves).optimal_bounds();
+            let bounds = Cube::SOLVED.twist_seq(parse_moves(&moves)).optimal_bounds();
 
             for bound in bounds {
                 if bound.upper == bound.lower {
@@ -198,32 +891,290 @@ fn main() {
             }
 
         },
-        Commands::Scramble { key , output} => {
-            let key = key.unwrap_or(HexString::get_random());
+        Commands::Stats { phase, output } => {
+            println!("Depth  States");
+
+            let stats = PhaseStats::generate(phase.into(), |depth, n_states| {
+                println!("{:>5}  {}", depth, n_states.to_string().yellow());
+            });
+
+            println!("Total: {} of {} states", stats.n_states(), stats.expected_n_states());
+            println!("Antipodes: {} at depth {}", stats.n_antipodes().to_string().yellow(), stats.max_depth());
+            for &cube_index in stats.antipodes() {
+                println!("  {}", new_scramble(cube_index).inverse().to_notation(output.into()));
+            }
 
-            let cube_index = key.to_cube_index();
+            if stats.n_states() != stats.expected_n_states() {
+                exit_with_error(format!("Only {} of the {} states were found", stats.n_states(), stats.expected_n_states()));
+            }
 
-            println!("Verification key: {}", key.to_string().yellow().underline());
-            println!("{}",  new_scramble(cube_index).to_notation(output.into()) )
+            if stats.max_depth() != stats.expected_max_depth() {
+                exit_with_error(format!("Max depth {} does not match the max depth of {} assumed by the solver", stats.max_depth(), stats.expected_max_depth()));
+            }
+
+            println!("Max depth: {} ({})", stats.max_depth(), "matches the solver".green());
         },
-        Commands::VerifyScramble { key, scramble} => {
-            let expected_scramble = new_scramble(key.to_cube_index());
+        Commands::Study { file, number, time_limit, mode, key } => {
+            let time_limit = Duration::try_from_secs_f64(time_limit).unwrap_or_else(|_| exit_with_error("The time limit must be a non-negative number of seconds"));
+
+            let mut study = if file.exists() {
+                let study = StudyFile::from_text(&read_input(&file)).unwrap_or_else(|err| exit_with_error(format!("Invalid study file {}: {}", file.display(), err)));
 
-            if expected_scramble == scramble.into_iter().collect() {
-                println!("{}", "Valid".green())
+                if key.is_some_and(|key| key != study.key) {
+                    exit_with_error(format!("The key does not match the key {} of the study", study.key));
+                }
+                if mode.is_some_and(|mode| StudyMode::from(mode) != study.mode) {
+                    exit_with_error(format!("The mode does not match the {} mode of the study", study.mode.name()));
+                }
+
+                println!("Resuming the study with {} of {} states searched", study.results.len(), number);
+                study
             } else {
-                println!("{}", "Invalid".red());
+                StudyFile::new(key.unwrap_or_else(random_scramble_key), mode.map_or(StudyMode::Optimal, StudyMode::from))
+            };
+
+            // rewrite the file in case the last result was only partly written
+            replace_output(&file, &study.to_text());
+
+            while study.results.len() < number {
+                let line = study.study_next(time_limit);
+                append_output(&file, &line);
+
+                let result = study.results.last().unwrap();
+                let length = match result.exact() {
+                    Some(length) => length.to_string().green(),
+                    None => format!("{}-{}", result.bound.lower, result.bound.upper).yellow(),
+                };
+                println!("Sample {}/{}: {} ({:.1} s)", study.results.len(), number, length, result.time.as_secs_f64());
+            }
+
+            println!("[{}]", "Summary".yellow().underline());
+            print!("{}", study.summary());
+        },
+        Commands::Scramble {
+            key,
+            tag,
+            output,
+            certificate,
+            subset,
+            min_length,
+            max_length,
+            min_phase1_depth,
+            max_phase1_depth,
+            min_phase2_depth,
+            max_phase2_depth,
+            min_phase3_depth,
+            max_phase3_depth,
+            max_candidates,
+        } => {
+            let key = key.unwrap_or_else(random_scramble_key);
+
+            let filter = ScrambleFilter {
+                min_length,
+                max_length,
+                min_phase_depths: [min_phase1_depth, min_phase2_depth, min_phase3_depth],
+                max_phase_depths: [max_phase1_depth, max_phase2_depth, max_phase3_depth],
+            };
+
+            let found = filter.find(&key, &tag, subset.into(), max_candidates).unwrap_or_else(|err| exit_with_error(err));
+
+            if !filter.is_empty() {
+                println!("Base key: {}", key);
+                println!("Rejected candidates: {}", found.rejections);
+            }
+
+            println!("Verification key: {}", found.key.to_string().yellow().underline());
+            println!("{}",  found.scramble.to_notation(output.into()) );
+
+            if let Some(path) = certificate {
+                let certificate = match filter.is_empty() {
+                    true => ScrambleCertificate::new(&found.key, &tag, subset.into()),
+                    false => ScrambleCertificate::with_filter(&key, &tag, subset.into(), &filter, found.rejections),
+                };
+
+                write_certificate(&path, &certificate.unwrap_or_else(|err| exit_with_error(err)));
+            }
+        },
+        Commands::ScrambleSet { key, tag, rounds, scrambles, extras, format, output, file } => {
+            let master_key = key.unwrap_or_else(random_scramble_key);
+
+            let set = ScrambleSet::new(master_key, tag, rounds, scrambles, extras).unwrap_or_else(|err| exit_with_error(err));
+
+            let contents = match format {
+                FormatEnum::Text => set.to_text(output.into()),
+                FormatEnum::Json => set.to_json(output.into()),
+            };
+
+            write_output(file.as_deref(), &contents);
+        },
+        Commands::VerifyScramble { key, scramble, tag, subset, mode, certificate} => {
+            if let Some(path) = certificate {
+                match read_certificate(&path).and_then(|certificate| certificate.verify()) {
+                    Ok(()) => println!("{}", "Valid".green()),
+                    Err(err) => println!("{} {}", "Invalid:".red(), err),
+                }
+                return;
+            }
+
+            let key = key.expect("the key is required without a certificate");
+            let cube_index = key.to_subset_cube_index(&tag, subset.into()).unwrap_or_else(|err| exit_with_error(err));
+            let verification = verify_scramble(cube_index, &parse_moves(&scramble));
+            let strict = matches!(mode, VerifyModeEnum::Strict);
+
+            match verification {
+                ScrambleVerification::Exact => println!("{}", "Valid".green()),
+                ScrambleVerification::SameState if !strict => println!("{} (same state, different moves)", "Valid".green()),
+                ScrambleVerification::SameState => println!("{} same state, different moves (use --mode state to accept)", "Invalid:".red()),
+                ScrambleVerification::DifferentState => println!("{} different state", "Invalid:".red()),
             }
         },
         Commands::Invert { moves, output } => {
-            let s = Notation::from(output).format_twist_sequence(&TwistSequence(moves).inverse());
+            let s = Notation::from(output).format_twist_sequence(&parse_moves(&moves).inverse());
 
             println!("{}", s)
         },
-        Commands::Convert { moves, output } => {
-            let s = Notation::from(output).format_twist_sequence(&TwistSequence(moves));
+        Commands::Convert { mut moves, output, input, log, file, keep_structure } => {
+            // the notation and a log file can also be given after the moves
+            let (output, file) = match moves.iter().position(|arg| NotationEnum::from_str(arg, true).is_ok()) {
+                None => (output.unwrap_or(NotationEnum::Standard), file),
+                Some(index) => {
+                    let mut positional = moves.split_off(index).into_iter();
+                    let notation = NotationEnum::from_str(&positional.next().unwrap(), true).unwrap();
+                    let positional_file = positional.next().map(PathBuf::from);
 
-            println!("{}", s)
+                    if positional.next().is_some() {
+                        exit_with_error("Only a log file can be given after the notation");
+                    }
+                    if output.is_some() {
+                        exit_with_error("The notation can't be given both after the moves and with --output");
+                    }
+                    if positional_file.is_some() && file.is_some() {
+                        exit_with_error("The log file can't be given both after the notation and with --file");
+                    }
+
+                    (notation, positional_file.or(file))
+                }
+            };
+
+            if log.is_some() && !moves.is_empty() {
+                exit_with_error("Moves can't be given with --log");
+            }
+            if keep_structure && file.is_some() {
+                exit_with_error("--keep-structure can't be used when writing a log file");
+            }
+
+            let algorithm = parse_algorithm(&moves, input.map(Notation::from));
+
+            if keep_structure {
+                println!("{}", algorithm.to_notation(output.into()));
+                return;
+            }
+
+            let from_log = log.is_some();
+            let (scramble, solution) = match log {
+                Some(path) => read_log(&path),
+                None => (algorithm.expand(), TwistSequence::default()),
+            };
+
+            match file {
+                Some(path) => {
+                    let log = match output {
+                        NotationEnum::MC4D => MC4DLog::new(scramble, solution).to_string(),
+                        NotationEnum::Hyperspeedcube => HyperspeedcubeLog::new(scramble, solution).to_string(),
+                        NotationEnum::Standard => exit_with_error("Standard notation has no log file format"),
+                    };
+
+                    write_output(Some(&path), &log);
+                }
+                None if from_log => print_log(&scramble, &solution, output.into()),
+                None => println!("{}", Notation::from(output).format_twist_sequence(&scramble)),
+            }
+        },
+        Commands::Simplify { moves, rotations, output } => {
+            let simplified = parse_moves(&moves).simplify_with(rotations.into());
+
+            println!("[{}]", format!("{} STM", simplified.len()).yellow().underline());
+            println!("{}", simplified.to_notation(output.into()));
+        },
+        Commands::Mirror { moves, axis, output } => {
+            let mirrored = parse_moves(&moves).mirror(axis.into());
+
+            println!("{}", mirrored.to_notation(output.into()));
+        },
+        Commands::Analyze { moves, compare } => {
+            let moves = parse_moves(&moves);
+            let cube = Cube::SOLVED.twist_seq(moves.iter().copied());
+
+            println!("[{}]", format!("{} STM", moves.len()).yellow().underline());
+            println!("Order: {}", moves.order().to_string().yellow());
+
+            let cycles = cube.cycles();
+            if cycles.is_empty() {
+                println!("Cycles: {}", "none".yellow());
+            } else {
+                println!("Cycles:");
+                for cycle in cycles {
+                    let locations = cycle.locations.iter().map(PieceLocation::to_string).collect::<Vec<_>>();
+                    println!("  ({}) order {}", locations.join(" "), cycle.order().to_string().yellow());
+                }
+            }
+
+            if let Some(compare) = compare {
+                let other = Cube::SOLVED.twist_seq(parse_moves(&[compare]));
+
+                let equivalence = if cube.is_equivalent(&other, false) {
+                    "yes".green()
+                } else if let Some(rotation) = cube.rotation_to(&other) {
+                    format!("up to rotation ({})", rotation.to_twists().to_notation(Notation::Standard)).yellow()
+                } else {
+                    "no".red()
+                };
+                println!("Equivalent: {equivalence}");
+            }
+        },
+        Commands::Show { moves, colors } => {
+            let cube = Cube::SOLVED.twist_seq(parse_moves(&moves));
+
+            print_net(&cube, &colors.clone().unwrap_or_default());
+
+            let facelets = match colors {
+                Some(scheme) => cube.to_facelet_string_with(&scheme),
+                None => cube.to_facelet_string(),
+            };
+            println!("Facelets: {}", facelets.yellow());
+        },
+        Commands::Render { moves, output, view, highlight, strip, sticker_size, colors, notation } => {
+            let moves = parse_moves(&moves);
+            let options = RenderOptions {
+                view: view.into(),
+                color_scheme: colors,
+                highlighted: highlight,
+                sticker_size,
+            };
+
+            let svg = if strip {
+                Cube::SOLVED.render_strip_svg(&moves, notation.into(), &options)
+            } else {
+                Cube::SOLVED.twist_seq(moves).render_svg(&options)
+            };
+
+            write_output(output.as_deref(), &svg);
+        },
+        Commands::ImportLog { file, output } => {
+            let (scramble, solution) = read_log(&file);
+
+            print_log(&scramble, &solution, output.into());
+        },
+        Commands::ExportLog { moves, solution, solve, output } => {
+            let scramble = parse_moves(&moves);
+
+            let solution = if solve {
+                Cube::SOLVED.twist_seq(scramble.iter().copied()).fast_solve()
+            } else {
+                parse_moves(&solution)
+            };
+
+            write_output(output.as_deref(), &MC4DLog::new(scramble, solution).to_string());
         },
     }
 }
\ No newline at end of file
@@ -0,0 +1,73 @@
+use colored::{Color, Colorize};
+
+use super::*;
+
+/// Width of a drawn cell in characters
+const CELL_WIDTH: usize = 9;
+
+/// Positions of the cells in the net
+const NET_LAYOUT: [[Option<Face>; 4]; 3] = [
+    [None, Some(Face::U), Some(Face::F), None],
+    [Some(Face::L), Some(Face::I), Some(Face::R), Some(Face::O)],
+    [None, Some(Face::D), Some(Face::B), None],
+];
+
+/// Returns the color used to draw stickers belonging to the face
+fn face_color(face: Face, scheme: &ColorScheme) -> Color {
+    let Rgb([r, g, b]) = scheme.color(face).rgb;
+    Color::TrueColor { r, g, b }
+}
+
+/// Returns the drawn row of a cell
+///
+/// A cell is drawn as its back layer followed by its front layer
+fn cell_row(facelets: &[Face; N_FACELETS], cell: Face, row: usize, scheme: &ColorScheme) -> String {
+    let mut string = String::new();
+
+    for layer in 0..2 {
+        if layer != 0 {
+            string.push(' ');
+        }
+
+        for column in 0..2 {
+            let facelet = facelets[cell as usize * 8 + layer * 4 + row * 2 + column];
+            string += &"  ".on_color(face_color(facelet, scheme)).to_string();
+        }
+    }
+
+    string
+}
+
+/// Prints the cube as a colored net of its eight cells
+///
+/// The `L`, `I`, `R`, and `O` cells are drawn in a row with the `U` and `D` cells above and below
+/// `I` and the `F` and `B` cells above and below `R`.
+pub fn print_net(cube: &Cube, scheme: &ColorScheme) {
+    let facelets = cube.facelets();
+
+    for cells in NET_LAYOUT {
+        let labels = cells
+            .iter()
+            .map(|cell| match cell {
+                Some(face) => format!("{:<CELL_WIDTH$}", face.to_string().bold()),
+                None => " ".repeat(CELL_WIDTH),
+            })
+            .collect::<Vec<_>>()
+            .join("  ");
+        println!("{}", labels.trim_end());
+
+        for row in 0..2 {
+            let line = cells
+                .iter()
+                .map(|cell| match cell {
+                    Some(face) => cell_row(&facelets, *face, row, scheme),
+                    None => " ".repeat(CELL_WIDTH),
+                })
+                .collect::<Vec<_>>()
+                .join("  ");
+            println!("{}", line.trim_end());
+        }
+
+        println!();
+    }
+}
@@ -0,0 +1,158 @@
+use super::*;
+
+/// A scramble in a [`ScrambleSet`]
+#[derive(Debug, Clone)]
+pub struct SetScramble {
+    /// The label the key of the scramble is derived with
+    pub label: String,
+    /// The verification key of the scramble
+    pub key: ScrambleKey,
+    /// The scramble moves
+    pub scramble: TwistSequence,
+}
+
+impl SetScramble {
+    /// Generates the scramble with the given label from the master key and tag
+    pub fn new(
+        master_key: &ScrambleKey,
+        tag: &str,
+        label: String,
+    ) -> Result<Self, ScrambleKeyError> {
+        let key = master_key.derive(&label);
+        let scramble = new_scramble(key.to_cube_index(tag)?);
+
+        Ok(SetScramble {
+            label,
+            key,
+            scramble,
+        })
+    }
+}
+
+/// The scrambles of one round of a [`ScrambleSet`]
+#[derive(Debug, Clone)]
+pub struct ScrambleRound {
+    /// The number of the round, starting from 1
+    pub round: usize,
+    /// The scrambles of the round
+    pub scrambles: Vec<SetScramble>,
+    /// Extra scrambles to use in place of scrambles that can't be used
+    pub extras: Vec<SetScramble>,
+}
+
+/// A set of scrambles for a competition which can be reproduced from a single master key
+///
+/// The key of each scramble is derived from the master key and a label naming the round and the
+/// scramble (e.g. `round 1 scramble 3` or `round 1 extra 2`), so each scramble can be verified on
+/// its own with its key and the key can be checked against the master key once it is published.
+#[derive(Debug, Clone)]
+pub struct ScrambleSet {
+    /// The key all scramble keys are derived from
+    pub master_key: ScrambleKey,
+    /// The tag the cube states of the scrambles are derived with
+    pub tag: String,
+    /// The rounds of the set
+    pub rounds: Vec<ScrambleRound>,
+}
+
+impl ScrambleSet {
+    /// Generates a set with the given number of rounds, scrambles per round and extra scrambles
+    /// per round
+    ///
+    /// Returns an error if a tag is given for a master key which doesn't support tags.
+    pub fn new(
+        master_key: ScrambleKey,
+        tag: String,
+        rounds: usize,
+        scrambles: usize,
+        extras: usize,
+    ) -> Result<Self, ScrambleKeyError> {
+        let set_scramble = |label| SetScramble::new(&master_key, &tag, label);
+
+        let rounds = (1..=rounds)
+            .map(|round| {
+                Ok(ScrambleRound {
+                    round,
+                    scrambles: (1..=scrambles)
+                        .map(|i| set_scramble(format!("round {round} scramble {i}")))
+                        .collect::<Result<_, _>>()?,
+                    extras: (1..=extras)
+                        .map(|i| set_scramble(format!("round {round} extra {i}")))
+                        .collect::<Result<_, _>>()?,
+                })
+            })
+            .collect::<Result<_, ScrambleKeyError>>()?;
+
+        Ok(ScrambleSet {
+            master_key,
+            tag,
+            rounds,
+        })
+    }
+
+    /// Returns the set as human readable text with the scrambles in the given notation
+    pub fn to_text(&self, notation: Notation) -> String {
+        let mut text = format!("Master key: {}\n", self.master_key);
+        if !self.tag.is_empty() {
+            text.push_str(&format!("Tag: {}\n", self.tag));
+        }
+
+        for round in &self.rounds {
+            text.push_str(&format!("\nRound {}\n", round.round));
+
+            let scrambles = round
+                .scrambles
+                .iter()
+                .enumerate()
+                .map(|(i, s)| ("Scramble", i, s));
+            let extras = round
+                .extras
+                .iter()
+                .enumerate()
+                .map(|(i, s)| ("Extra", i, s));
+
+            for (kind, i, scramble) in scrambles.chain(extras) {
+                text.push_str(&format!(
+                    "{kind} {} [{}]: {}\n",
+                    i + 1,
+                    scramble.key,
+                    scramble.scramble.to_notation(notation)
+                ));
+            }
+        }
+
+        text
+    }
+
+    /// Returns the set as JSON with the scrambles in the given notation
+    pub fn to_json(&self, notation: Notation) -> String {
+        let scramble_json = |scramble: &SetScramble| {
+            serde_json::json!({
+                "label": scramble.label,
+                "key": scramble.key.to_string(),
+                "scramble": scramble.scramble.to_notation(notation),
+            })
+        };
+
+        let rounds = self
+            .rounds
+            .iter()
+            .map(|round| {
+                serde_json::json!({
+                    "round": round.round,
+                    "scrambles": round.scrambles.iter().map(scramble_json).collect::<Vec<_>>(),
+                    "extras": round.extras.iter().map(scramble_json).collect::<Vec<_>>(),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let json = serde_json::json!({
+            "master_key": self.master_key.to_string(),
+            "tag": self.tag,
+            "notation": format!("{notation:?}"),
+            "rounds": rounds,
+        });
+
+        serde_json::to_string_pretty(&json).unwrap() + "\n"
+    }
+}
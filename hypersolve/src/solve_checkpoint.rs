@@ -0,0 +1,328 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// Errors for reading optimal solve checkpoints
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum SolveCheckpointError {
+    #[error("invalid checkpoint file: {0}")]
+    InvalidFile(String),
+    #[error("invalid moves in the checkpoint: {0}")]
+    InvalidMoves(ParseAlgorithmError),
+    #[error(transparent)]
+    Checkpoint(#[from] CheckpointError),
+}
+
+/// How many solutions an optimal solve has printed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SolveProgress {
+    /// The number of solutions printed
+    pub solutions: usize,
+    /// The length of the last solution printed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length: Option<usize>,
+    /// The number of solutions printed with the length of the last solution
+    pub solutions_at_length: usize,
+}
+
+/// The position of the search of one phase in a [`SolveCheckpoint`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SolvePhaseCheckpoint {
+    /// The length of the sequences solving the phase which are being searched
+    pub length: usize,
+    /// The last sequence solving the phase in standard notation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_sequence: Option<String>,
+}
+
+/// A checkpoint of an optimal solve which the solve can be resumed from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SolveCheckpoint {
+    /// The version of Hypersolve which wrote the checkpoint
+    pub version: String,
+    /// The scramble in standard notation
+    pub scramble: String,
+    /// The solutions printed before the checkpoint
+    pub progress: SolveProgress,
+    /// The length of the solutions being searched
+    pub solution_length: usize,
+    /// The length which the search stops at without searching it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length_limit: Option<usize>,
+    /// The position of the search of each phase which has started, starting with phase 1
+    pub phases: Vec<SolvePhaseCheckpoint>,
+}
+
+impl SolveCheckpoint {
+    /// Creates the checkpoint of the search for solutions to the scramble
+    pub fn new(
+        scramble: &TwistSequence,
+        checkpoint: &SearchCheckpoint,
+        progress: SolveProgress,
+    ) -> Self {
+        SolveCheckpoint {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            scramble: scramble.to_notation(Notation::Standard),
+            progress,
+            solution_length: checkpoint.solution_length,
+            length_limit: checkpoint.length_limit,
+            phases: checkpoint
+                .phases
+                .iter()
+                .map(|phase| SolvePhaseCheckpoint {
+                    length: phase.length,
+                    last_sequence: phase
+                        .last_sequence
+                        .as_ref()
+                        .map(|sequence| sequence.to_notation(Notation::Standard)),
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the scramble
+    pub fn scramble(&self) -> Result<TwistSequence, SolveCheckpointError> {
+        Notation::Standard
+            .parse_twist_sequence(&self.scramble)
+            .map_err(SolveCheckpointError::InvalidMoves)
+    }
+
+    /// Returns the position of the search
+    pub fn search_checkpoint(&self) -> Result<SearchCheckpoint, SolveCheckpointError> {
+        let phases = self
+            .phases
+            .iter()
+            .map(|phase| {
+                Ok(PhaseCheckpoint {
+                    length: phase.length,
+                    last_sequence: match &phase.last_sequence {
+                        None => None,
+                        Some(sequence) => Some(
+                            Notation::Standard
+                                .parse_twist_sequence(sequence)
+                                .map_err(SolveCheckpointError::InvalidMoves)?,
+                        ),
+                    },
+                })
+            })
+            .collect::<Result<_, SolveCheckpointError>>()?;
+
+        Ok(SearchCheckpoint {
+            solution_length: self.solution_length,
+            length_limit: self.length_limit,
+            phases,
+        })
+    }
+
+    /// Returns the search for solutions to the scramble resumed from the checkpoint
+    pub fn resume(&self) -> Result<ShortestSolutionIterator, SolveCheckpointError> {
+        let cube = Cube::SOLVED.twist_seq(self.scramble()?);
+
+        Ok(cube.resume_solutions(&self.search_checkpoint()?)?)
+    }
+
+    /// Parses a checkpoint from JSON
+    pub fn from_json_str(s: &str) -> Result<Self, SolveCheckpointError> {
+        serde_json::from_str(s).map_err(|err| SolveCheckpointError::InvalidFile(err.to_string()))
+    }
+
+    /// Formats the checkpoint as JSON
+    pub fn to_json_string(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a checkpoint should be serializable") + "\n"
+    }
+}
+
+/// A search for solutions in order of increasing length which an optimal solve prints
+pub trait SolutionSearch {
+    /// Returns the next solution, or an error if the deadline passes before it is found
+    fn next_before(&mut self, deadline: Option<Instant>)
+        -> Result<Option<TwistSequence>, TimedOut>;
+
+    /// Sets the search to begin outputing solutions of the given length
+    fn set_to_length(&mut self, solution_length: usize);
+
+    /// Returns the position of the search, or `None` if the search can't be resumed
+    fn checkpoint(&self) -> Option<SearchCheckpoint>;
+}
+
+impl SolutionSearch for ShortestSolutionIterator {
+    fn next_before(
+        &mut self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<TwistSequence>, TimedOut> {
+        ShortestSolutionIterator::next_before(self, deadline)
+    }
+
+    fn set_to_length(&mut self, solution_length: usize) {
+        ShortestSolutionIterator::set_to_length(self, solution_length)
+    }
+
+    fn checkpoint(&self) -> Option<SearchCheckpoint> {
+        Some(ShortestSolutionIterator::checkpoint(self))
+    }
+}
+
+impl SolutionSearch for ParallelSolutionIterator {
+    fn next_before(
+        &mut self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<TwistSequence>, TimedOut> {
+        ParallelSolutionIterator::next_before(self, deadline)
+    }
+
+    fn set_to_length(&mut self, solution_length: usize) {
+        ParallelSolutionIterator::set_to_length(self, solution_length)
+    }
+
+    // the branches are searched out of order, so there is no single position to resume from
+    fn checkpoint(&self) -> Option<SearchCheckpoint> {
+        None
+    }
+}
+
+impl<I: SolutionSearch + Iterator<Item = TwistSequence>> SolutionSearch
+    for RankedSolutionIterator<I>
+{
+    // the solutions of each length are held back until the whole length is searched, so the
+    // search can't stop at a deadline
+    fn next_before(
+        &mut self,
+        _deadline: Option<Instant>,
+    ) -> Result<Option<TwistSequence>, TimedOut> {
+        Ok(self.next())
+    }
+
+    fn set_to_length(&mut self, solution_length: usize) {
+        self.restart_with(|solutions| solutions.set_to_length(solution_length))
+    }
+
+    fn checkpoint(&self) -> Option<SearchCheckpoint> {
+        None
+    }
+}
+
+impl SolutionSearch for Box<dyn SolutionSearch> {
+    fn next_before(
+        &mut self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<TwistSequence>, TimedOut> {
+        self.as_mut().next_before(deadline)
+    }
+
+    fn set_to_length(&mut self, solution_length: usize) {
+        self.as_mut().set_to_length(solution_length)
+    }
+
+    fn checkpoint(&self) -> Option<SearchCheckpoint> {
+        self.as_ref().checkpoint()
+    }
+}
+
+/// Periodically writes checkpoints of an optimal solve to a file
+#[derive(Debug, Clone)]
+pub struct CheckpointWriter {
+    path: PathBuf,
+    scramble: TwistSequence,
+    interval: Duration,
+    last_write: Instant,
+}
+
+impl CheckpointWriter {
+    /// Creates a writer of checkpoints of the search for solutions to the scramble
+    pub fn new(path: &Path, scramble: TwistSequence, interval: Duration) -> Self {
+        CheckpointWriter {
+            path: path.to_path_buf(),
+            scramble,
+            interval,
+            last_write: Instant::now(),
+        }
+    }
+
+    /// Returns when the next checkpoint is due
+    pub fn deadline(&self) -> Instant {
+        self.last_write + self.interval
+    }
+
+    /// Writes the checkpoint of the search over the previous one, exiting if the file cannot be
+    /// written
+    pub fn write(&mut self, checkpoint: &SearchCheckpoint, progress: SolveProgress) {
+        let checkpoint = SolveCheckpoint::new(&self.scramble, checkpoint, progress);
+        replace_output(&self.path, &checkpoint.to_json_string());
+
+        self.last_write = Instant::now();
+    }
+
+    /// Removes the checkpoint once the search is finished, exiting if the file cannot be removed
+    pub fn finish(self) {
+        if let Err(err) = std::fs::remove_file(&self.path) {
+            if err.kind() != std::io::ErrorKind::NotFound {
+                exit_with_error(format!("Failed to remove {}: {}", self.path.display(), err));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the scramble and the search for its solutions after the first solution
+    fn search() -> (TwistSequence, ShortestSolutionIterator) {
+        let scramble = Notation::Standard
+            .parse_twist_sequence("RO2 UF2 IF2 FR2")
+            .unwrap();
+
+        let mut solutions = Cube::SOLVED.twist_seq(scramble.clone()).solutions(..=4);
+        solutions.next().unwrap();
+
+        (scramble, solutions)
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let (scramble, solutions) = search();
+        let progress = SolveProgress {
+            solutions: 1,
+            length: Some(4),
+            solutions_at_length: 1,
+        };
+
+        let checkpoint = SolveCheckpoint::new(&scramble, &solutions.checkpoint(), progress);
+        let parsed = SolveCheckpoint::from_json_str(&checkpoint.to_json_string()).unwrap();
+        assert_eq!(parsed, checkpoint);
+        assert_eq!(parsed.progress, progress);
+        assert_eq!(parsed.scramble().unwrap(), scramble);
+        assert_eq!(parsed.search_checkpoint().unwrap(), solutions.checkpoint());
+
+        assert!(matches!(
+            SolveCheckpoint::from_json_str("{}"),
+            Err(SolveCheckpointError::InvalidFile(_))
+        ));
+
+        let mut invalid = checkpoint.clone();
+        invalid.phases[0].last_sequence = Some("XX".to_string());
+        assert!(matches!(
+            invalid.search_checkpoint(),
+            Err(SolveCheckpointError::InvalidMoves(_))
+        ));
+    }
+
+    #[test]
+    fn test_resume() {
+        let (scramble, mut solutions) = search();
+        let checkpoint =
+            SolveCheckpoint::new(&scramble, &solutions.checkpoint(), SolveProgress::default());
+
+        let mut resumed = SolveCheckpoint::from_json_str(&checkpoint.to_json_string())
+            .unwrap()
+            .resume()
+            .unwrap();
+        let next = solutions.next();
+        assert!(next.is_some());
+        assert_eq!(resumed.next(), next);
+        assert_eq!(resumed.collect::<Vec<_>>(), solutions.collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,384 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+use super::*;
+
+/// The first line of every study file
+const STUDY_FILE_HEADER: &str = "# hypersolve study";
+
+/// The line naming the columns of the records in a study file
+const STUDY_FILE_COLUMNS: &str = "sample,cube_index,lower,upper,exact,seconds";
+
+/// Errors for reading study files
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum StudyFileError {
+    #[error("the file is not a study file")]
+    NotStudyFile,
+    #[error("the file doesn't give a {0}")]
+    MissingField(&'static str),
+    #[error("invalid verification key: {0}")]
+    InvalidKey(ScrambleKeyError),
+    #[error("unknown study mode `{0}`")]
+    InvalidMode(String),
+    #[error("invalid record on line {0}")]
+    InvalidRecord(usize),
+    #[error("expected sample {expected} on line {line}")]
+    SampleOutOfOrder { line: usize, expected: usize },
+}
+
+/// The results of a study of the optimal solution lengths of randomly sampled states
+///
+/// The state of each sample is derived from the key and the number of the sample, so a study can
+/// be reproduced from its key and resumed from its results. The file format is a header giving
+/// the key and the mode followed by one line of comma separated values for each sample, so results
+/// can be appended as soon as they are found.
+#[derive(Debug, Clone)]
+pub struct StudyFile {
+    /// The key the states are derived from
+    pub key: ScrambleKey,
+    /// How the states are searched
+    pub mode: StudyMode,
+    /// The result of each sample in order, starting from sample 1
+    pub results: Vec<StudyResult>,
+}
+
+impl StudyFile {
+    /// Creates a study without any results
+    pub fn new(key: ScrambleKey, mode: StudyMode) -> Self {
+        StudyFile {
+            key,
+            mode,
+            results: Vec::new(),
+        }
+    }
+
+    /// Returns the index of the state of the sample with the given number
+    pub fn sample_cube_index(&self, sample: usize) -> CubeIndex {
+        self.key
+            .derive(&format!("study sample {sample}"))
+            .to_cube_index("")
+            .expect("a key without a tag should give a cube index")
+    }
+
+    /// Searches the state of the next sample and adds its result to the study, returning the
+    /// line of the file for the result
+    pub fn study_next(&mut self, time_limit: Duration) -> String {
+        let sample = self.results.len() + 1;
+        let result = study_state(self.sample_cube_index(sample), self.mode, time_limit);
+
+        self.results.push(result);
+
+        Self::record_line(sample, &result)
+    }
+
+    /// Returns the header of the file
+    pub fn header(&self) -> String {
+        format!(
+            "{STUDY_FILE_HEADER}\n# key: {}\n# mode: {}\n{STUDY_FILE_COLUMNS}\n",
+            self.key,
+            self.mode.name()
+        )
+    }
+
+    /// Returns the line of the file for the result of the sample
+    fn record_line(sample: usize, result: &StudyResult) -> String {
+        format!(
+            "{sample},{},{},{},{},{:.3}\n",
+            result.cube_index,
+            result.bound.lower,
+            result.bound.upper,
+            result.exact().map_or(String::new(), |len| len.to_string()),
+            result.time.as_secs_f64()
+        )
+    }
+
+    /// Formats the study as the contents of a file
+    pub fn to_text(&self) -> String {
+        let mut text = self.header();
+
+        for (i, result) in self.results.iter().enumerate() {
+            text.push_str(&Self::record_line(i + 1, result));
+        }
+
+        text
+    }
+
+    /// Parses a study from the contents of a file
+    ///
+    /// A last line without a line break is ignored since it was left by a study which was
+    /// interrupted while writing it.
+    pub fn from_text(s: &str) -> Result<Self, StudyFileError> {
+        let complete = &s[..s.rfind('\n').map_or(0, |i| i + 1)];
+        let mut lines = complete.lines().enumerate().map(|(i, line)| (i + 1, line));
+
+        if lines.next().map(|(_, line)| line.trim()) != Some(STUDY_FILE_HEADER) {
+            return Err(StudyFileError::NotStudyFile);
+        }
+
+        let mut key = None;
+        let mut mode = None;
+        let mut results = Vec::new();
+
+        for (line_number, line) in lines {
+            let line = line.trim();
+
+            if let Some(field) = line.strip_prefix('#') {
+                match field
+                    .split_once(':')
+                    .map(|(name, value)| (name.trim(), value.trim()))
+                {
+                    Some(("key", value)) => {
+                        key = Some(value.parse().map_err(StudyFileError::InvalidKey)?)
+                    }
+                    Some(("mode", value)) => {
+                        mode = Some(
+                            StudyMode::ALL
+                                .into_iter()
+                                .find(|mode| mode.name() == value)
+                                .ok_or_else(|| StudyFileError::InvalidMode(value.to_string()))?,
+                        )
+                    }
+                    _ => (),
+                }
+                continue;
+            }
+
+            if line.is_empty() || line == STUDY_FILE_COLUMNS {
+                continue;
+            }
+
+            let (sample, result) =
+                Self::parse_record(line).ok_or(StudyFileError::InvalidRecord(line_number))?;
+
+            if sample != results.len() + 1 {
+                return Err(StudyFileError::SampleOutOfOrder {
+                    line: line_number,
+                    expected: results.len() + 1,
+                });
+            }
+
+            results.push(result);
+        }
+
+        Ok(StudyFile {
+            key: key.ok_or(StudyFileError::MissingField("key"))?,
+            mode: mode.ok_or(StudyFileError::MissingField("mode"))?,
+            results,
+        })
+    }
+
+    /// Parses the line of the file for the result of a sample
+    fn parse_record(line: &str) -> Option<(usize, StudyResult)> {
+        let [sample, cube_index, lower, upper, exact, seconds] = line
+            .split(',')
+            .map(str::trim)
+            .collect::<Vec<_>>()
+            .try_into()
+            .ok()?;
+
+        let result = StudyResult {
+            cube_index: CubeIndex::try_from(cube_index.parse::<u128>().ok()?).ok()?,
+            bound: Bound {
+                upper: upper.parse().ok()?,
+                lower: lower.parse().ok()?,
+            },
+            time: Duration::try_from_secs_f64(seconds.parse().ok()?).ok()?,
+        };
+
+        let is_exact = !exact.is_empty();
+        if result.bound.lower > result.bound.upper
+            || is_exact != result.exact().is_some()
+            || (is_exact && exact.parse::<usize>().ok()? != result.bound.lower)
+        {
+            return None;
+        }
+
+        Some((sample.parse().ok()?, result))
+    }
+
+    /// Returns the number of samples with each value of the length, skipping samples without one
+    pub fn histogram(
+        &self,
+        length: impl Fn(&StudyResult) -> Option<usize>,
+    ) -> BTreeMap<usize, usize> {
+        let mut histogram = BTreeMap::new();
+
+        for length in self.results.iter().filter_map(length) {
+            *histogram.entry(length).or_default() += 1;
+        }
+
+        histogram
+    }
+
+    /// Returns a summary of the results with a histogram of the optimal solution lengths and
+    /// bounds
+    pub fn summary(&self) -> String {
+        let exact = self.histogram(StudyResult::exact);
+        let lower = self.histogram(|result| Some(result.bound.lower));
+        let upper = self.histogram(|result| Some(result.bound.upper));
+
+        let mut text = format!(
+            "Optimal solution length found for {} of {} states\n",
+            exact.values().sum::<usize>(),
+            self.results.len()
+        );
+
+        // lengths without any samples are left out
+        let lengths = lower
+            .keys()
+            .chain(upper.keys())
+            .copied()
+            .collect::<BTreeSet<_>>();
+        if !lengths.is_empty() {
+            text.push_str(&format!(
+                "\n{:>6}  {:>7}  {:>5}  {:>5}\n",
+                "Length", "Optimal", "Lower", "Upper"
+            ));
+
+            for length in lengths {
+                let count = |histogram: &BTreeMap<usize, usize>| {
+                    histogram.get(&length).copied().unwrap_or_default()
+                };
+
+                text.push_str(&format!(
+                    "{length:>6}  {:>7}  {:>5}  {:>5}\n",
+                    count(&exact),
+                    count(&lower),
+                    count(&upper)
+                ));
+            }
+            text.push('\n');
+        }
+
+        if let Some(length) = exact.keys().last() {
+            text.push_str(&format!("Longest optimal solution: {length}\n"));
+        }
+        if let Some(bound) = upper.keys().last() {
+            text.push_str(&format!(
+                "Largest upper bound: {bound} (the solver's upper bound on God's number is {GODS_NUMBER_UPPER_BOUND})\n"
+            ));
+        }
+
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: ScrambleKey = ScrambleKey::new(KeyVersion::V1, [5; 16]);
+
+    /// Returns a study with one exact result and one result with only bounds
+    fn study() -> StudyFile {
+        let mut study = StudyFile::new(KEY, StudyMode::Bounds);
+        study.results = vec![
+            StudyResult {
+                cube_index: study.sample_cube_index(1),
+                bound: Bound {
+                    upper: 15,
+                    lower: 15,
+                },
+                time: Duration::from_millis(1500),
+            },
+            StudyResult {
+                cube_index: study.sample_cube_index(2),
+                bound: Bound {
+                    upper: 17,
+                    lower: 14,
+                },
+                time: Duration::from_millis(250),
+            },
+        ];
+        study
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let study = study();
+        let text = study.to_text();
+        assert!(text.starts_with(&study.header()));
+
+        let parsed = StudyFile::from_text(&text).unwrap();
+        assert_eq!(parsed.key, study.key);
+        assert_eq!(parsed.mode, study.mode);
+        assert_eq!(parsed.results, study.results);
+        assert_eq!(parsed.to_text(), text);
+    }
+
+    #[test]
+    fn test_parse() {
+        let study = study();
+        let text = format!(
+            "{STUDY_FILE_HEADER}\n#mode: bounds\n\n# key:  {KEY} \n{STUDY_FILE_COLUMNS}\n\
+             1, {}, 15, 15, 15, 1.5\n2,{},14,17,,0.250\n",
+            study.results[0].cube_index, study.results[1].cube_index
+        );
+
+        let parsed = StudyFile::from_text(&text).unwrap();
+        assert_eq!(parsed.key, KEY);
+        assert_eq!(parsed.mode, StudyMode::Bounds);
+        assert_eq!(parsed.results, study.results);
+
+        // a last line without a line break was only partly written
+        let partial = format!("{}3,12", study.to_text());
+        assert_eq!(
+            StudyFile::from_text(&partial).unwrap().results,
+            study.results
+        );
+    }
+
+    #[test]
+    fn test_malformed() {
+        let study = study();
+        let text = study.to_text();
+        let header = study.header();
+        let error = |text: &str| StudyFile::from_text(text).unwrap_err();
+
+        assert!(matches!(error(""), StudyFileError::NotStudyFile));
+        assert!(matches!(
+            error(&text.replacen(STUDY_FILE_HEADER, "# study", 1)),
+            StudyFileError::NotStudyFile
+        ));
+        assert!(matches!(
+            error(&text.replacen("# key:", "# seed:", 1)),
+            StudyFileError::MissingField("key")
+        ));
+        assert!(matches!(
+            error(&text.replacen("# mode: bounds", "# mode: fast", 1)),
+            StudyFileError::InvalidMode(mode) if mode == "fast"
+        ));
+        assert!(matches!(
+            error(&text.replacen("# key: ", "# key: x", 1)),
+            StudyFileError::InvalidKey(_)
+        ));
+
+        // the records start on line 5
+        for record in [
+            "1,2,3\n",
+            "1,x,15,15,15,1.5\n",
+            "1,12,16,15,,1.5\n",
+            "1,12,15,15,,1.5\n",
+            "1,12,14,15,14,1.5\n",
+            "1,12,15,15,15,-1\n",
+        ] {
+            assert!(
+                matches!(
+                    error(&format!("{header}{record}")),
+                    StudyFileError::InvalidRecord(5)
+                ),
+                "{record}"
+            );
+        }
+
+        let lines = text.lines().collect::<Vec<_>>();
+        let swapped = format!("{header}{}\n{}\n", lines[5], lines[4]);
+        assert!(matches!(
+            error(&swapped),
+            StudyFileError::SampleOutOfOrder {
+                line: 5,
+                expected: 1
+            }
+        ));
+    }
+}
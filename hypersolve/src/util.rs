@@ -1,3 +1,10 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use colored::Colorize;
+
+use super::*;
+
 /// Capitalizes the first letter in the given input string
 pub fn capitalize_first_letter(s: &str) -> String {
     let mut c = s.chars();
@@ -6,3 +13,188 @@ pub fn capitalize_first_letter(s: &str) -> String {
         Some(f) => f.to_uppercase().collect::<String>() + c.as_str(),
     }
 }
+
+/// Prints the error message and exits
+pub fn exit_with_error(message: impl std::fmt::Display) -> ! {
+    eprintln!("{}", message.to_string().red());
+    std::process::exit(1)
+}
+
+/// Writes the contents to the file or prints them if no file is given, exiting if the file
+/// cannot be written
+pub fn write_output(path: Option<&Path>, contents: &str) {
+    match path {
+        None => print!("{}", contents),
+        Some(path) => {
+            if let Err(err) = std::fs::write(path, contents) {
+                exit_with_error(format!("Failed to write {}: {}", path.display(), err));
+            }
+        }
+    }
+}
+
+/// Writes the contents to a temporary file and moves it over the file, exiting if the file cannot
+/// be written
+///
+/// An interruption never leaves the file partly written.
+pub fn replace_output(path: &Path, contents: &str) {
+    let mut temp_path = path.to_path_buf().into_os_string();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+
+    write_output(Some(&temp_path), contents);
+    if let Err(err) = std::fs::rename(&temp_path, path) {
+        exit_with_error(format!("Failed to write {}: {}", path.display(), err));
+    }
+}
+
+/// Appends the contents to the file, exiting if the file cannot be written
+pub fn append_output(path: &Path, contents: &str) {
+    let result = std::fs::OpenOptions::new()
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(contents.as_bytes()));
+
+    if let Err(err) = result {
+        exit_with_error(format!("Failed to write {}: {}", path.display(), err));
+    }
+}
+
+/// Reads the contents of the file, exiting if the file cannot be read
+pub fn read_input(path: &Path) -> String {
+    std::fs::read_to_string(path).unwrap_or_else(|err| {
+        exit_with_error(format!("Failed to read {}: {}", path.display(), err))
+    })
+}
+
+/// Generates a random verification key with the latest key version
+pub fn random_scramble_key() -> ScrambleKey {
+    ScrambleKey::new(KeyVersion::LATEST, HexString::<16>::get_random().bytes())
+}
+
+/// Parses moves given as separate arguments, exiting if they are invalid
+pub fn parse_moves(moves: &[String]) -> TwistSequence {
+    parse_algorithm(moves, None).expand()
+}
+
+/// Parses moves given as separate arguments in the given notation (or detecting the notation of
+/// each twist if none is given) keeping their structure, exiting if they are invalid
+pub fn parse_algorithm(moves: &[String], notation: Option<Notation>) -> Algorithm {
+    let moves = moves.join(" ");
+
+    match notation {
+        Some(notation) => notation.parse_algorithm_spanned(&moves),
+        None => Algorithm::parse_spanned(&moves),
+    }
+    .unwrap_or_else(|errors| exit_with_parse_errors(&moves, &errors))
+}
+
+/// Prints each error in the moves with a caret under its location and exits
+fn exit_with_parse_errors(moves: &str, errors: &[SpannedParseError]) -> ! {
+    for error in errors {
+        let start = error.span.start;
+        let line_start = moves[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = moves[start..].find('\n').map_or(moves.len(), |i| start + i);
+
+        let column = moves[line_start..start].chars().count();
+        let width = moves[error.span.clone()].chars().count().max(1);
+
+        eprintln!("{} {}", "Invalid moves:".red(), error);
+        eprintln!("  {}", &moves[line_start..line_end]);
+        eprintln!("  {}{}", " ".repeat(column), "^".repeat(width).red());
+
+        if !error.suggestions.is_empty() {
+            let suggestions = error
+                .suggestions
+                .iter()
+                .map(|suggestion| suggestion.yellow().to_string())
+                .collect::<Vec<_>>();
+
+            eprintln!("  Did you mean {}?", suggestions.join(", "));
+        }
+    }
+
+    std::process::exit(1)
+}
+
+/// Returns the preset color scheme with the given name or loads it from a TOML or JSON file
+pub fn parse_color_scheme(s: &str) -> Result<ColorScheme, ColorSchemeError> {
+    if let Ok(scheme) = s.parse() {
+        return Ok(scheme);
+    }
+
+    let path = Path::new(s);
+    let contents = std::fs::read_to_string(path).map_err(|err| match err.kind() {
+        std::io::ErrorKind::NotFound => ColorSchemeError::UnknownPreset(s.to_string()),
+        _ => ColorSchemeError::InvalidFile(err.to_string()),
+    })?;
+
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => ColorScheme::from_json_str(&contents),
+        _ => ColorScheme::from_toml_str(&contents),
+    }
+}
+
+/// Returns whether the file should be read and written as TOML rather than JSON
+fn is_toml_file(path: &Path) -> bool {
+    path.extension().and_then(|extension| extension.to_str()) == Some("toml")
+}
+
+/// Writes the certificate to the file as TOML if it has a `.toml` extension and as JSON otherwise,
+/// exiting if the file cannot be written
+pub fn write_certificate(path: &Path, certificate: &ScrambleCertificate) {
+    let contents = match is_toml_file(path) {
+        true => certificate.to_toml_string(),
+        false => certificate.to_json_string(),
+    };
+
+    write_output(Some(path), &contents);
+}
+
+/// Reads a certificate written by [`write_certificate`]
+pub fn read_certificate(path: &Path) -> Result<ScrambleCertificate, CertificateError> {
+    let contents = read_input(path);
+
+    match is_toml_file(path) {
+        true => ScrambleCertificate::from_toml_str(&contents),
+        false => ScrambleCertificate::from_json_str(&contents),
+    }
+}
+
+/// Reads the scramble and solution from an MC4D or Hyperspeedcube log file, exiting if the log
+/// cannot be read
+pub fn read_log(path: &Path) -> (TwistSequence, TwistSequence) {
+    let contents = read_input(path);
+
+    if contents.starts_with("MagicCube4D") {
+        let log = contents
+            .parse::<MC4DLog>()
+            .unwrap_or_else(|err| exit_with_error(format!("Invalid MC4D log: {}", err)));
+
+        (log.scramble, log.solution)
+    } else {
+        let log = contents
+            .parse::<HyperspeedcubeLog>()
+            .unwrap_or_else(|err| exit_with_error(format!("Invalid Hyperspeedcube log: {}", err)));
+
+        (log.scramble, log.solution)
+    }
+}
+
+/// Prints the scramble and solution of a log in the given notation
+pub fn print_log(scramble: &TwistSequence, solution: &TwistSequence, notation: Notation) {
+    println!(
+        "[{}]",
+        format!("Scramble ({} STM)", scramble.len())
+            .yellow()
+            .underline()
+    );
+    println!("{}", scramble.to_notation(notation));
+    println!(
+        "[{}]",
+        format!("Solution ({} STM)", solution.len())
+            .yellow()
+            .underline()
+    );
+    println!("{}", solution.to_notation(notation));
+}